@@ -1,7 +1,24 @@
-use crate::util::strip_prefix_0x;
-use anyhow::Result;
-use ckb_sdk::util::zeroize_privkey;
-use ckb_types::{H160, H256};
+use crate::util::{eos_pubkey_checksum, strip_prefix_0x, TRON_ADDRESS_PREFIX};
+use anyhow::{anyhow, bail, ensure, Result};
+use bitcoin::hashes::hex::FromHex;
+use bitcoin::util::{
+    address::{Address as BtcAddress, Payload, WitnessVersion},
+    base58,
+};
+use ckb_jsonrpc_types as json_types;
+use ckb_sdk::{
+    constants::{SIGHASH_TYPE_HASH, VALUE_MASK},
+    util::{blake160, zeroize_privkey, zeroize_slice},
+    Address, Since, SinceType,
+};
+use ckb_types::{
+    bytes::Bytes,
+    core::{EpochNumberWithFraction, ScriptHashType},
+    packed::Script,
+    prelude::*,
+    H160, H256,
+};
+use p256::ecdsa::{SigningKey, VerifyingKey};
 use std::str::FromStr;
 
 #[allow(clippy::wrong_self_convention)]
@@ -31,6 +48,12 @@ impl ArgParser<secp256k1::PublicKey> for secp256k1::PublicKey {
     }
 }
 
+impl ArgParser<secp256k1::XOnlyPublicKey> for secp256k1::XOnlyPublicKey {
+    fn parse(s: &str) -> Result<secp256k1::XOnlyPublicKey> {
+        Ok(secp256k1::XOnlyPublicKey::from_str(strip_prefix_0x(s))?)
+    }
+}
+
 #[derive(Clone)]
 pub struct PrivkeyWrapper(pub secp256k1::SecretKey);
 
@@ -58,3 +81,326 @@ impl ArgParser<PrivkeyWrapper> for PrivkeyArgParser {
         Ok(ret)
     }
 }
+
+/// Parse a private key in hex string or in WIF(wallet import format) used by bitcoin like chains.
+pub struct WifPrivkeyArgParser;
+
+impl ArgParser<PrivkeyWrapper> for WifPrivkeyArgParser {
+    fn parse(s: &str) -> Result<PrivkeyWrapper> {
+        if let Ok(key) = PrivkeyArgParser::parse(s) {
+            return Ok(key);
+        }
+        let mut data = base58::from_check(s)?;
+        // 1 byte version, 32 bytes key, and an optional compressed flag 0x01
+        ensure!(
+            data.len() == 33 || (data.len() == 34 && data[33] == 1),
+            "invalid WIF private key"
+        );
+        let ret = secp256k1::SecretKey::from_slice(&data[1..33]).map(PrivkeyWrapper);
+        zeroize_slice(&mut data);
+        Ok(ret?)
+    }
+}
+
+/// Parse a bitcoin P2PKH, P2SH-P2WPKH or P2WPKH address into the 20 bytes hash it carries.
+pub struct BitcoinAddressParser;
+
+impl ArgParser<H160> for BitcoinAddressParser {
+    fn parse(s: &str) -> Result<H160> {
+        let address = BtcAddress::from_str(s)?;
+        let hash = match address.payload {
+            Payload::PubkeyHash(hash) => hash[..].to_vec(),
+            Payload::ScriptHash(hash) => hash[..].to_vec(),
+            Payload::WitnessProgram {
+                version: WitnessVersion::V0,
+                program,
+            } if program.len() == 20 => program,
+            _ => bail!("only P2PKH, P2SH-P2WPKH and P2WPKH bitcoin addresses are supported"),
+        };
+        Ok(H160::from_slice(&hash)?)
+    }
+}
+
+/// The version bytes of dogecoin P2PKH addresses on mainnet and testnet.
+const DOGECOIN_P2PKH_VERSIONS: [u8; 2] = [0x1e, 0x71];
+
+/// Parse a base58 dogecoin P2PKH address into the public key hash it carries.
+pub struct DogecoinAddressParser;
+
+impl ArgParser<H160> for DogecoinAddressParser {
+    fn parse(s: &str) -> Result<H160> {
+        let data = base58::from_check(s)?;
+        ensure!(
+            data.len() == 21,
+            "invalid dogecoin address length: {}",
+            data.len()
+        );
+        ensure!(
+            DOGECOIN_P2PKH_VERSIONS.contains(&data[0]),
+            "unsupported dogecoin address version byte 0x{:02x}, only P2PKH addresses are supported",
+            data[0]
+        );
+        Ok(H160::from_slice(&data[1..])?)
+    }
+}
+
+/// Parse an EOS public key in the legacy `EOS` format or the `PUB_K1_` format.
+pub struct EosPubkeyParser;
+
+impl ArgParser<secp256k1::PublicKey> for EosPubkeyParser {
+    fn parse(s: &str) -> Result<secp256k1::PublicKey> {
+        let (key, suffix) = if let Some(key) = s.strip_prefix("PUB_K1_") {
+            (key, &b"K1"[..])
+        } else if let Some(key) = s.strip_prefix("EOS") {
+            (key, &b""[..])
+        } else {
+            bail!("the EOS public key should start with `EOS` or `PUB_K1_`");
+        };
+        let data = base58::from(key)?;
+        ensure!(
+            data.len() == 37,
+            "invalid EOS public key length: {}",
+            data.len()
+        );
+        ensure!(
+            eos_pubkey_checksum(&data[..33], suffix) == data[33..],
+            "invalid checksum of the EOS public key"
+        );
+        Ok(secp256k1::PublicKey::from_slice(&data[..33])?)
+    }
+}
+
+/// Parse a base58check tron address(`T...`) into the 20 bytes hash it carries.
+pub struct TronAddressParser;
+
+impl ArgParser<H160> for TronAddressParser {
+    fn parse(s: &str) -> Result<H160> {
+        let data = base58::from_check(s)?;
+        ensure!(
+            data.len() == 21 && data[0] == TRON_ADDRESS_PREFIX,
+            "invalid tron address {}",
+            s
+        );
+        Ok(H160::from_slice(&data[1..])?)
+    }
+}
+
+/// Parse a sighash address into its lock arg, the blake160 hash of the public key.
+pub struct SighashAddressParser;
+
+impl ArgParser<H160> for SighashAddressParser {
+    fn parse(s: &str) -> Result<H160> {
+        let address = Address::from_str(s).map_err(|err| anyhow!(err))?;
+        sighash_lock_arg(&address)
+    }
+}
+
+/// The lock arg of a sighash address, the blake160 hash of the public key.
+pub fn sighash_lock_arg(address: &Address) -> Result<H160> {
+    let lock_args = address.payload().args();
+    ensure!(
+        address.payload().code_hash(None).as_slice() == SIGHASH_TYPE_HASH.as_bytes()
+            && address.payload().hash_type() == ScriptHashType::Type
+            && lock_args.len() == 20,
+        "{} is not a valid sighash address",
+        address
+    );
+    Ok(H160::from_slice(lock_args.as_ref())?)
+}
+
+/// Parse a secp256k1 public key in hex string into its blake160 hash.
+pub struct PubkeyBlake160Parser;
+
+impl ArgParser<H160> for PubkeyBlake160Parser {
+    fn parse(s: &str) -> Result<H160> {
+        let pubkey = secp256k1::PublicKey::parse(s)?;
+        Ok(blake160(&pubkey.serialize()))
+    }
+}
+
+/// Parse a multisig member given by its sighash address, its public key (hex string, compressed
+/// or uncompressed) or the blake160 hash of its public key, into the pubkey hash.
+pub struct MultisigMemberParser;
+
+impl ArgParser<H160> for MultisigMemberParser {
+    fn parse(s: &str) -> Result<H160> {
+        if Address::from_str(s).is_ok() {
+            return SighashAddressParser::parse(s);
+        }
+        match strip_prefix_0x(s).len() {
+            40 => H160::parse(s),
+            66 | 130 => PubkeyBlake160Parser::parse(s),
+            _ => bail!(
+                "{} is not a sighash address, a public key or a pubkey hash of the multisig member",
+                s
+            ),
+        }
+    }
+}
+
+/// Parse a script in the JSON format of the CKB RPC, e.g. `{"code_hash":"0x...","hash_type":"type","args":"0x..."}`.
+pub struct ScriptArgParser;
+
+impl ArgParser<Script> for ScriptArgParser {
+    fn parse(s: &str) -> Result<Script> {
+        let script: json_types::Script = serde_json::from_str(s)?;
+        Ok(script.into())
+    }
+}
+
+/// Parse bytes in hex string, with or without the `0x` prefix.
+pub struct HexBytesParser;
+
+impl ArgParser<Bytes> for HexBytesParser {
+    fn parse(s: &str) -> Result<Bytes> {
+        let data = Vec::<u8>::from_hex(strip_prefix_0x(s))?;
+        Ok(Bytes::from(data))
+    }
+}
+
+/// Parse a P-256 public key in hex string, SEC1 compressed or uncompressed, or the 64 bytes x | y.
+pub struct P256PubkeyParser;
+
+impl ArgParser<VerifyingKey> for P256PubkeyParser {
+    fn parse(s: &str) -> Result<VerifyingKey> {
+        let mut data = HexBytesParser::parse(s)?.to_vec();
+        if data.len() == 64 {
+            data.insert(0, 0x04);
+        }
+        VerifyingKey::from_sec1_bytes(&data)
+            .map_err(|err| anyhow!("invalid P-256 public key: {}", err))
+    }
+}
+
+/// Parse a P-256 private key in hex string.
+pub struct P256PrivkeyParser;
+
+impl ArgParser<SigningKey> for P256PrivkeyParser {
+    fn parse(s: &str) -> Result<SigningKey> {
+        let data = H256::parse(s)?;
+        SigningKey::from_slice(data.as_bytes())
+            .map_err(|err| anyhow!("invalid P-256 private key: {}", err))
+    }
+}
+
+/// Parse an ed25519 public key in hex string.
+pub struct Ed25519PubkeyParser;
+
+impl ArgParser<ed25519_dalek::VerifyingKey> for Ed25519PubkeyParser {
+    fn parse(s: &str) -> Result<ed25519_dalek::VerifyingKey> {
+        let data = H256::parse(s)?;
+        Ok(ed25519_dalek::VerifyingKey::from_bytes(&data.0)?)
+    }
+}
+
+/// Parse a Solana address, the ed25519 public key in base58.
+pub struct SolanaAddressParser;
+
+impl ArgParser<ed25519_dalek::VerifyingKey> for SolanaAddressParser {
+    fn parse(s: &str) -> Result<ed25519_dalek::VerifyingKey> {
+        let data = base58::from(s)?;
+        ensure!(data.len() == 32, "invalid Solana address");
+        let mut pubkey = [0u8; 32];
+        pubkey.copy_from_slice(&data);
+        Ok(ed25519_dalek::VerifyingKey::from_bytes(&pubkey)?)
+    }
+}
+
+/// Parse an ed25519 private key, the 32 bytes seed in hex string, or the 64 bytes Solana
+/// keypair (seed | public key) in base58.
+pub struct Ed25519PrivkeyParser;
+
+impl ArgParser<ed25519_dalek::SigningKey> for Ed25519PrivkeyParser {
+    fn parse(s: &str) -> Result<ed25519_dalek::SigningKey> {
+        if let Ok(seed) = H256::parse(s) {
+            return Ok(ed25519_dalek::SigningKey::from_bytes(&seed.0));
+        }
+        let mut data = base58::from(s)?;
+        ensure!(data.len() == 64, "invalid ed25519 private key");
+        let mut keypair = [0u8; 64];
+        keypair.copy_from_slice(&data);
+        let key = ed25519_dalek::SigningKey::from_keypair_bytes(&keypair);
+        zeroize_slice(&mut data);
+        zeroize_slice(&mut keypair);
+        Ok(key?)
+    }
+}
+
+/// Parse a script hash type: `data`, `type` or `data1`.
+pub struct HashTypeParser;
+
+impl ArgParser<ScriptHashType> for HashTypeParser {
+    fn parse(s: &str) -> Result<ScriptHashType> {
+        match s {
+            "data" => Ok(ScriptHashType::Data),
+            "type" => Ok(ScriptHashType::Type),
+            "data1" => Ok(ScriptHashType::Data1),
+            _ => bail!(
+                "invalid hash type {}, should be one of data, type or data1",
+                s
+            ),
+        }
+    }
+}
+
+/// Parse a since value: `[relative:](block|epoch|timestamp):VALUE`, the epoch is in
+/// `NUMBER[.INDEX/LENGTH]` format and the timestamp is in seconds; or a raw since value in hex.
+pub struct SinceArgParser;
+
+impl ArgParser<u64> for SinceArgParser {
+    fn parse(s: &str) -> Result<u64> {
+        if s.starts_with("0x") || s.starts_with("0X") {
+            let since = Since::from_raw_value(u64::from_str_radix(strip_prefix_0x(s), 16)?);
+            ensure!(
+                since.flags_is_valid(),
+                "invalid flags of the since value {}",
+                s
+            );
+            return Ok(since.value());
+        }
+        let (is_relative, rest) = if let Some(rest) = s.strip_prefix("relative:") {
+            (true, rest)
+        } else {
+            (false, s.strip_prefix("absolute:").unwrap_or(s))
+        };
+        let (metric, value) = rest
+            .split_once(':')
+            .ok_or_else(|| anyhow!("invalid since value {}", s))?;
+        let (since_type, value) = match metric {
+            "block" => (SinceType::BlockNumber, value.parse::<u64>()?),
+            "epoch" => (SinceType::EpochNumberWithFraction, parse_epoch(value)?),
+            "timestamp" => (SinceType::Timestamp, value.parse::<u64>()?),
+            _ => bail!(
+                "invalid since metric {}, should be one of block, epoch or timestamp",
+                metric
+            ),
+        };
+        ensure!(value <= VALUE_MASK, "the since value {} is too large", s);
+        Ok(Since::new(since_type, value, is_relative).value())
+    }
+}
+
+/// Parse an epoch in `NUMBER[.INDEX/LENGTH]` format into its full value.
+fn parse_epoch(s: &str) -> Result<u64> {
+    let (number, index, length) = if let Some((number, fraction)) = s.split_once('.') {
+        let (index, length) = fraction
+            .split_once('/')
+            .ok_or_else(|| anyhow!("invalid epoch {}, should be NUMBER.INDEX/LENGTH", s))?;
+        (
+            number.parse::<u64>()?,
+            index.parse::<u64>()?,
+            length.parse::<u64>()?,
+        )
+    } else {
+        (s.parse::<u64>()?, 0, 1)
+    };
+    ensure!(
+        number < EpochNumberWithFraction::NUMBER_MAXIMUM_VALUE
+            && length > 0
+            && length < EpochNumberWithFraction::LENGTH_MAXIMUM_VALUE
+            && index < length,
+        "invalid epoch {}",
+        s
+    );
+    Ok(EpochNumberWithFraction::new(number, index, length).full_value())
+}
@@ -0,0 +1,183 @@
+use std::{io::Write, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use ckb_crypto::secp::Pubkey;
+use ckb_sdk::{
+    unlock::{IdentityFlag, OmniLockConfig},
+    util::{blake160, keccak160},
+};
+use ckb_types::H160;
+use clap::{ArgGroup, Args};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    arg_parser::{
+        ArgParser, BitcoinAddressParser, DogecoinAddressParser, EosPubkeyParser,
+        PubkeyBlake160Parser, SighashAddressParser, TronAddressParser,
+    },
+    build_addr::{address_network, p2pkh_hash, AddressEnv, OmniLockAddress},
+    flags::OmniLockFlagArgs,
+    identity::{auth_flag_from_name, auth_flag_name},
+    keystore::CkbKeyStore,
+    sdk_compat::new_omnilock_config,
+    util::{create_new_file, strip_prefix_0x},
+};
+
+#[derive(Args)]
+#[clap(group(ArgGroup::new("source").required(true).args(&["csv", "keystore"])))]
+pub(crate) struct BatchArgs {
+    /// The CSV file of the users, with the header `id,auth,key`. The auth is one of pubkey-hash, ethereum, eos, tron, bitcoin and dogecoin, the key is the public key or the address of the auth
+    #[clap(long, value_name = "PATH")]
+    csv: Option<PathBuf>,
+
+    /// Build the pubkey-hash addresses of all the keys in the ckb-cli keystore
+    #[clap(long)]
+    keystore: bool,
+
+    /// The new output file, in CSV if the extension is `.csv`, or in JSON; the JSON is printed if not given
+    #[clap(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+#[derive(Deserialize)]
+struct BatchInput {
+    id: String,
+    auth: String,
+    key: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct BatchOutput {
+    id: String,
+    auth: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mainnet: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    testnet: Option<String>,
+    lock_arg: String,
+    lock_hash: String,
+}
+
+fn is_hex(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Whether the key is a 20 bytes hash in hex string.
+fn is_hash160(key: &str) -> bool {
+    let key = strip_prefix_0x(key);
+    key.len() == 40 && is_hex(key)
+}
+
+/// Whether the key is a compressed or uncompressed public key in hex string.
+fn is_pubkey(key: &str) -> bool {
+    let key = strip_prefix_0x(key);
+    (key.len() == 66 || key.len() == 130) && is_hex(key)
+}
+
+/// The omnilock config of a user, from the public key or the address of the auth.
+fn batch_auth_config(auth: &str, key: &str) -> Result<OmniLockConfig> {
+    let flag = auth_flag_from_name(auth)?;
+    let auth_content = match flag {
+        IdentityFlag::PubkeyHash => {
+            if key.starts_with("ck") {
+                SighashAddressParser::parse(key)?
+            } else if is_hash160(key) {
+                H160::parse(key)?
+            } else {
+                PubkeyBlake160Parser::parse(key)?
+            }
+        }
+        IdentityFlag::Ethereum | IdentityFlag::Tron => {
+            if flag == IdentityFlag::Tron && key.starts_with('T') {
+                TronAddressParser::parse(key)?
+            } else if is_hash160(key) {
+                H160::parse(key)?
+            } else {
+                let pubkey = secp256k1::PublicKey::parse(key)?;
+                keccak160(Pubkey::from(pubkey).as_ref())
+            }
+        }
+        IdentityFlag::Eos => blake160(&EosPubkeyParser::parse(key)?.serialize()),
+        IdentityFlag::Bitcoin | IdentityFlag::Dogecoin => {
+            if is_pubkey(key) {
+                p2pkh_hash(None, Some(key.to_owned()), None)?
+            } else if flag == IdentityFlag::Bitcoin {
+                BitcoinAddressParser::parse(key)?
+            } else {
+                DogecoinAddressParser::parse(key)?
+            }
+        }
+        _ => bail!("the auth {} is not supported in batch", auth),
+    };
+    new_omnilock_config(flag, auth_content)
+}
+
+fn load_inputs(args: &BatchArgs) -> Result<Vec<BatchInput>> {
+    if let Some(path) = args.csv.as_ref() {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_path(path)
+            .with_context(|| format!("try to read file {}", path.display()))?;
+        reader
+            .deserialize()
+            .enumerate()
+            .map(|(i, row)| {
+                row.with_context(|| format!("try to parse row {} of {}", i + 1, path.display()))
+            })
+            .collect()
+    } else {
+        let mut keystore = CkbKeyStore::load_default()?;
+        Ok(keystore
+            .accounts()
+            .into_iter()
+            .map(|lock_arg| BatchInput {
+                id: format!("{:#x}", lock_arg),
+                auth: auth_flag_name(IdentityFlag::PubkeyHash).to_owned(),
+                key: format!("{:#x}", lock_arg),
+            })
+            .collect())
+    }
+}
+
+/// Build the omnilock addresses of many users, the omnilock script is only loaded once.
+pub(crate) fn build_batch_addr(args: BatchArgs, env: &AddressEnv) -> Result<()> {
+    let inputs = load_inputs(&args)?;
+    let info = env.config.omnilock_info()?;
+    let network = address_network(env)?;
+    let mut outputs = Vec::with_capacity(inputs.len());
+    for (i, input) in inputs.into_iter().enumerate() {
+        let mut config = batch_auth_config(&input.auth, &input.key)
+            .with_context(|| format!("row {}, id {}", i + 1, input.id))?;
+        args.flag_args.apply(&mut config)?;
+        let address = OmniLockAddress::new(&config, &info.script_id, network);
+        outputs.push(BatchOutput {
+            id: input.id,
+            auth: input.auth,
+            mainnet: address.mainnet,
+            testnet: address.testnet,
+            lock_arg: address.lock_arg,
+            lock_hash: address.lock_hash,
+        });
+    }
+
+    match args.output {
+        Some(path) if path.extension().is_some_and(|ext| ext == "csv") => {
+            let mut writer = csv::Writer::from_writer(create_new_file(&path)?);
+            for output in outputs.iter() {
+                writer.serialize(output)?;
+            }
+            writer.flush()?;
+        }
+        Some(path) => {
+            create_new_file(&path)?
+                .write_all(serde_json::to_string_pretty(&outputs)?.as_bytes())
+                .with_context(|| format!("try to write file {}", path.display()))?;
+        }
+        None => println!("{}", serde_json::to_string_pretty(&outputs)?),
+    }
+    Ok(())
+}
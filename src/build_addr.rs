@@ -1,18 +1,40 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::PathBuf};
 
-use crate::{arg_parser::ArgParser, client::build_omnilock_cell_dep, config::ConfigContext};
+use crate::{
+    arg_parser::{
+        sighash_lock_arg, ArgParser, BitcoinAddressParser, DogecoinAddressParser,
+        Ed25519PubkeyParser, EosPubkeyParser, HashTypeParser, P256PrivkeyParser, P256PubkeyParser,
+        PrivkeyArgParser, PrivkeyWrapper, PubkeyBlake160Parser, ScriptArgParser,
+        SighashAddressParser, SolanaAddressParser, TronAddressParser, WifPrivkeyArgParser,
+    },
+    batch_addr::{build_batch_addr, BatchArgs},
+    client::get_network_type,
+    config::ConfigContext,
+    export::{export_address, ExportFormat},
+    flags::{flags_details, OmniLockFlagArgs},
+    hd_wallet::{
+        derive_privkey, load_seed, PathTemplate, CKB_PATH_TEMPLATE, ETHEREUM_PATH_TEMPLATE,
+    },
+    identity::IdentityFile,
+    passkey::{p256_pubkey_bytes, p256_pubkey_hash, PasskeyAlgorithm},
+    sdk_compat::new_omnilock_config,
+    unlock::{dl_preimage, exec_preimage, DelegateAlgorithm},
+    util::{eos_pubkey_string, hash160, strip_prefix_0x, tron_address_string},
+};
+use bitcoin::util::base58;
 use ckb_crypto::secp::Pubkey;
 use ckb_sdk::{
-    constants::SIGHASH_TYPE_HASH,
-    unlock::{MultisigConfig, OmniLockConfig},
-    util::keccak160,
-    Address, NetworkType, SECP256K1,
+    unlock::{IdentityFlag, MultisigConfig, OmniLockConfig},
+    util::{blake160, keccak160},
+    Address, CkbRpcClient, NetworkType, ScriptId, SECP256K1,
 };
-use ckb_types::{core::ScriptHashType, packed::Script, prelude::*, H160, H256};
-use clap::{ArgGroup, Args, Subcommand};
+use ckb_types::{bytes::Bytes, core::ScriptHashType, packed::Script, prelude::*, H160, H256};
+use clap::{Arg, ArgGroup, ArgMatches, Args, Command, FromArgMatches, Subcommand};
+use p256::ecdsa::{SigningKey, VerifyingKey};
 
 use anyhow::{anyhow, bail, ensure, Result};
 use jsonrpc_core::Value;
+use serde::Serialize;
 use serde_json::json;
 #[derive(Args)]
 pub(crate) struct PubkeyHashArgs {
@@ -22,6 +44,9 @@ pub(crate) struct PubkeyHashArgs {
     /// The receiver's blake160 hash of a public key, lock-arg
     #[clap(long, value_name = "HASH", value_parser=H160::parse)]
     pubkey_hash: Option<H160>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
 }
 
 #[derive(Args)]
@@ -57,6 +82,90 @@ pub(crate) struct EthereumArgs {
         value_parser
     )]
     to_print_addr: bool,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+#[derive(Args)]
+#[clap(group(
+    ArgGroup::new("key")
+        .required(true)
+        .args(&["eos-pubkey", "eos-privkey"]),
+))]
+pub(crate) struct EosArgs {
+    /// The receiver's EOS public key, in `EOS...` or `PUB_K1_...` format
+    #[clap(long, value_name = "PUB_KEY", value_parser=EosPubkeyParser::parse)]
+    eos_pubkey: Option<secp256k1::PublicKey>,
+    /// The receiver's private key (WIF or hex string)
+    #[clap(long, value_name = "PRV_KEY", value_parser=WifPrivkeyArgParser::parse)]
+    eos_privkey: Option<PrivkeyWrapper>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+#[derive(Args)]
+#[clap(group(
+    ArgGroup::new("key")
+        .required(true)
+        .args(&["tron-address", "tron-privkey", "tron-pubkey"]),
+))]
+pub(crate) struct TronArgs {
+    /// The receiver's tron address, base58check `T...` format
+    #[clap(long, value_name = "ADDRESS", value_parser=TronAddressParser::parse)]
+    tron_address: Option<H160>,
+    /// The receiver's private key (hex string)
+    #[clap(long, value_name = "PRV_KEY", value_parser=PrivkeyArgParser::parse)]
+    tron_privkey: Option<PrivkeyWrapper>,
+    /// The receiver's pub key (hex string)
+    #[clap(long, value_name = "PUB_KEY")]
+    tron_pubkey: Option<String>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+#[derive(Args)]
+#[clap(group(
+    ArgGroup::new("key")
+        .required(true)
+        .args(&["bitcoin-address", "bitcoin-privkey", "bitcoin-pubkey"]),
+))]
+pub(crate) struct BitcoinArgs {
+    /// The receiver's bitcoin address, P2PKH, P2SH-P2WPKH or P2WPKH
+    #[clap(long, value_name = "ADDRESS", value_parser=BitcoinAddressParser::parse)]
+    bitcoin_address: Option<H160>,
+    /// The receiver's private key (hex string or WIF), the P2PKH address of the compressed public key will be used
+    #[clap(long, value_name = "PRV_KEY", value_parser=WifPrivkeyArgParser::parse)]
+    bitcoin_privkey: Option<PrivkeyWrapper>,
+    /// The receiver's pub key (hex string), the P2PKH address is built from the key in the form it is given, compressed or uncompressed
+    #[clap(long, value_name = "PUB_KEY")]
+    bitcoin_pubkey: Option<String>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+#[derive(Args)]
+#[clap(group(
+    ArgGroup::new("key")
+        .required(true)
+        .args(&["dogecoin-address", "dogecoin-privkey", "dogecoin-pubkey"]),
+))]
+pub(crate) struct DogecoinArgs {
+    /// The receiver's dogecoin P2PKH address
+    #[clap(long, value_name = "ADDRESS", value_parser=DogecoinAddressParser::parse)]
+    dogecoin_address: Option<H160>,
+    /// The receiver's private key (hex string or WIF), the P2PKH address of the compressed public key will be used
+    #[clap(long, value_name = "PRV_KEY", value_parser=WifPrivkeyArgParser::parse)]
+    dogecoin_privkey: Option<PrivkeyWrapper>,
+    /// The receiver's pub key (hex string), the P2PKH address is built from the key in the form it is given, compressed or uncompressed
+    #[clap(long, value_name = "PUB_KEY")]
+    dogecoin_pubkey: Option<String>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
 }
 
 #[derive(Args)]
@@ -69,9 +178,330 @@ pub(crate) struct MultiSigArgs {
     #[clap(long, value_name = "NUM")]
     threshold: u8,
 
-    /// Normal sighash addresses
-    #[clap(long, multiple_values = true, value_name = "ADDRESS")]
-    sighash_address: Vec<Address>,
+    #[clap(flatten)]
+    member_args: MultisigMemberArgs,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+/// The members of a multisig config, which can be given by sighash addresses, public keys and
+/// pubkey hashes in any mix. The arguments are parsed by hand to keep the members in the order
+/// they are given across the options.
+pub(crate) struct MultisigMemberArgs {
+    pub members: Vec<H160>,
+}
+
+const MULTISIG_MEMBER_ARGS: [&str; 3] = ["sighash-address", "pubkey", "pubkey-hash"];
+
+impl FromArgMatches for MultisigMemberArgs {
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        let mut members = Vec::new();
+        for id in MULTISIG_MEMBER_ARGS {
+            if let (Some(values), Some(indices)) =
+                (matches.get_many::<H160>(id), matches.indices_of(id))
+            {
+                members.extend(indices.zip(values.cloned()));
+            }
+        }
+        members.sort_by_key(|(index, _)| *index);
+        Ok(MultisigMemberArgs {
+            members: members.into_iter().map(|(_, member)| member).collect(),
+        })
+    }
+
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+        *self = Self::from_arg_matches(matches)?;
+        Ok(())
+    }
+}
+
+impl Args for MultisigMemberArgs {
+    fn augment_args(cmd: Command<'_>) -> Command<'_> {
+        let member = |id: &'static str| {
+            Arg::new(id)
+                .long(id)
+                .takes_value(true)
+                .multiple_values(true)
+                .multiple_occurrences(true)
+        };
+        cmd.arg(
+            member("sighash-address")
+                .value_name("ADDRESS")
+                .value_parser(SighashAddressParser::parse)
+                .help("Normal sighash addresses of the multisig members"),
+        )
+        .arg(
+            member("pubkey")
+                .value_name("PUBKEY")
+                .value_parser(PubkeyBlake160Parser::parse)
+                .help(
+                    "Public keys of the multisig members (hex string, compressed or uncompressed)",
+                ),
+        )
+        .arg(
+            member("pubkey-hash")
+                .value_name("HASH")
+                .value_parser(H160::parse)
+                .help("Blake160 hashes of the public keys of the multisig members, the lock-args of their sighash addresses"),
+        )
+    }
+
+    fn augment_args_for_update(cmd: Command<'_>) -> Command<'_> {
+        Self::augment_args(cmd)
+    }
+}
+
+#[derive(Args)]
+#[clap(group(
+    ArgGroup::new("owner")
+        .required(true)
+        .args(&["owner-address", "owner-lock"]),
+))]
+pub(crate) struct OwnerLockArgs {
+    /// The owner's CKB address, any lock script is accepted
+    #[clap(long, value_name = "ADDRESS")]
+    owner_address: Option<Address>,
+    /// The owner's lock script in json format, e.g. '{"code_hash":"0x..","hash_type":"type","args":"0x.."}'
+    #[clap(long, value_name = "SCRIPT", value_parser=ScriptArgParser::parse)]
+    owner_lock: Option<Script>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+/// Where the script to exec is, the exec part of the preimage before the pubkey hash.
+#[derive(Args)]
+pub(crate) struct ExecCodeArgs {
+    /// The code hash of the script to exec
+    #[clap(long, value_name = "HASH", value_parser=H256::parse)]
+    code_hash: H256,
+    /// The hash type of the script to exec: data, type or data1
+    #[clap(long, value_name = "HASH_TYPE", value_parser=HashTypeParser::parse)]
+    hash_type: ScriptHashType,
+    /// Where the code of the script is located, 0: cell dep, 1: witness
+    #[clap(long, value_name = "PLACE", default_value = "0", value_parser = clap::value_parser!(u8).range(0..=1))]
+    place: u8,
+    /// The bounds of the code to exec, the high 32 bits are the offset and the low 32 bits are the length, zero length means to the end
+    #[clap(long, value_name = "BOUNDS", default_value = "0")]
+    bounds: u64,
+}
+
+impl ExecCodeArgs {
+    /// The exec preimage of the pubkey hash the script verifies the signature with.
+    pub(crate) fn preimage(&self, pubkey_hash: &H160) -> Bytes {
+        exec_preimage(
+            &self.code_hash,
+            self.hash_type,
+            self.place,
+            self.bounds,
+            pubkey_hash,
+        )
+    }
+}
+
+#[derive(Args)]
+pub(crate) struct ExecArgs {
+    #[clap(flatten)]
+    code_args: ExecCodeArgs,
+    /// The blake160 hash of the public key the script verifies the signature with
+    #[clap(long, value_name = "HASH", value_parser=H160::parse)]
+    pubkey_hash: H160,
+    /// The signature algorithm the script verifies, it decides the length of the signature in the witness
+    #[clap(
+        long,
+        value_name = "ALGORITHM",
+        value_enum,
+        default_value = "secp256k1"
+    )]
+    algorithm: DelegateAlgorithm,
+}
+
+impl ExecArgs {
+    pub(crate) fn preimage(&self) -> Bytes {
+        self.code_args.preimage(&self.pubkey_hash)
+    }
+
+    pub(crate) fn algorithm(&self) -> DelegateAlgorithm {
+        self.algorithm
+    }
+}
+
+#[derive(Args)]
+pub(crate) struct DlArgs {
+    /// The code hash of the dynamic library
+    #[clap(long, value_name = "HASH", value_parser=H256::parse)]
+    code_hash: H256,
+    /// The hash type of the dynamic library: data, type or data1
+    #[clap(long, value_name = "HASH_TYPE", value_parser=HashTypeParser::parse)]
+    hash_type: ScriptHashType,
+    /// The identity the dynamic library verifies the signature with, the blake160 hash of the public key
+    #[clap(long, value_name = "HASH", value_parser=H160::parse)]
+    pubkey_hash: H160,
+    /// The signature algorithm the dynamic library verifies, it decides the length of the signature in the witness
+    #[clap(
+        long,
+        value_name = "ALGORITHM",
+        value_enum,
+        default_value = "secp256k1"
+    )]
+    algorithm: DelegateAlgorithm,
+}
+
+impl DlArgs {
+    pub(crate) fn preimage(&self) -> Bytes {
+        dl_preimage(&self.code_hash, self.hash_type, &self.pubkey_hash)
+    }
+
+    pub(crate) fn code_hash(&self) -> &H256 {
+        &self.code_hash
+    }
+
+    pub(crate) fn hash_type(&self) -> ScriptHashType {
+        self.hash_type
+    }
+
+    pub(crate) fn algorithm(&self) -> DelegateAlgorithm {
+        self.algorithm
+    }
+}
+
+#[derive(Args)]
+pub(crate) struct ExecAddressArgs {
+    #[clap(flatten)]
+    exec_args: ExecArgs,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+#[derive(Args)]
+pub(crate) struct DlAddressArgs {
+    #[clap(flatten)]
+    dl_args: DlArgs,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+/// The verifier of a signature algorithm, delegated to by dynamic linking or by exec.
+#[derive(Args)]
+pub(crate) struct VerifierArgs {
+    /// The code hash of the verifier, a dynamic library or a script to exec
+    #[clap(long, value_name = "HASH", value_parser=H256::parse)]
+    code_hash: H256,
+    /// The hash type of the verifier: data, type or data1
+    #[clap(long, value_name = "HASH_TYPE", value_parser=HashTypeParser::parse)]
+    hash_type: ScriptHashType,
+    /// Delegate to the verifier by exec from the cell dep, instead of dynamic linking
+    #[clap(long)]
+    exec: bool,
+}
+
+impl VerifierArgs {
+    /// The auth flag and the preimage of the pubkey hash the verifier checks the signature with,
+    /// the exec preimage locates the code in the whole cell dep.
+    pub(crate) fn preimage(&self, pubkey_hash: &H160) -> (IdentityFlag, Bytes) {
+        if self.exec {
+            let preimage = exec_preimage(&self.code_hash, self.hash_type, 0, 0, pubkey_hash);
+            (IdentityFlag::Exec, preimage)
+        } else {
+            let preimage = dl_preimage(&self.code_hash, self.hash_type, pubkey_hash);
+            (IdentityFlag::Dl, preimage)
+        }
+    }
+}
+
+#[derive(Args)]
+#[clap(group(
+    ArgGroup::new("key")
+        .required(true)
+        .args(&["p256-pubkey", "p256-key"]),
+))]
+pub(crate) struct PasskeyArgs {
+    #[clap(flatten)]
+    verifier_args: VerifierArgs,
+    /// The signature the verifier checks, the WebAuthn assertion of the passkey or the raw secp256r1 signature of the message
+    #[clap(long, value_name = "ALGORITHM", value_enum, default_value = "webauthn")]
+    algorithm: PasskeyAlgorithm,
+    /// The P-256 public key of the passkey (hex string, SEC1 compressed or uncompressed)
+    #[clap(long, value_name = "PUB_KEY", value_parser=P256PubkeyParser::parse)]
+    p256_pubkey: Option<VerifyingKey>,
+    /// The P-256 private key of a software authenticator (hex string)
+    #[clap(long, value_name = "KEY", value_parser=P256PrivkeyParser::parse)]
+    p256_key: Option<SigningKey>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+#[derive(Args)]
+pub(crate) struct SchnorrArgs {
+    #[clap(flatten)]
+    code_args: ExecCodeArgs,
+    /// The BIP340 x-only public key (32 bytes hex string)
+    #[clap(long, value_name = "PUB_KEY", value_parser=secp256k1::XOnlyPublicKey::parse)]
+    xonly_pubkey: secp256k1::XOnlyPublicKey,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+#[derive(Args)]
+#[clap(group(
+    ArgGroup::new("pubkey")
+        .required(true)
+        .args(&["ed25519-pubkey", "solana-address"]),
+))]
+pub(crate) struct Ed25519Args {
+    #[clap(flatten)]
+    verifier_args: VerifierArgs,
+    /// The ed25519 public key (32 bytes hex string)
+    #[clap(long, value_name = "PUB_KEY", value_parser=Ed25519PubkeyParser::parse)]
+    ed25519_pubkey: Option<ed25519_dalek::VerifyingKey>,
+    /// The Solana address, the ed25519 public key in base58
+    #[clap(long, value_name = "ADDRESS", value_parser=SolanaAddressParser::parse)]
+    solana_address: Option<ed25519_dalek::VerifyingKey>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+#[derive(Args)]
+pub(crate) struct IdentityArgs {
+    /// The identity file (.yaml or .json) of the auth and the flags
+    #[clap(long, value_name = "PATH")]
+    identity: PathBuf,
+}
+
+#[derive(Args)]
+pub(crate) struct MnemonicArgs {
+    /// The file of the BIP39 mnemonic words
+    #[clap(long, value_name = "PATH")]
+    mnemonic_file: PathBuf,
+
+    /// Prompt for the BIP39 passphrase of the mnemonic
+    #[clap(long)]
+    mnemonic_passphrase: bool,
+
+    /// The auth type of the derived keys
+    #[clap(long, value_name = "AUTH", default_value = "pubkey-hash", value_parser = ["pubkey-hash", "ethereum"])]
+    auth: String,
+
+    /// The BIP32 derivation path, the `i` level is replaced by the index: `m/44'/309'/0'/0/i` for pubkey-hash and `m/44'/60'/0'/0/i` for ethereum by default
+    #[clap(long, value_name = "PATH")]
+    derivation_path: Option<String>,
+
+    /// The first index to derive
+    #[clap(long, value_name = "INDEX", default_value = "0")]
+    start_index: u32,
+
+    /// The number of the indexes to derive
+    #[clap(long, value_name = "COUNT", default_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
+    count: u32,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
 }
 
 #[derive(Subcommand)]
@@ -80,68 +510,100 @@ pub(crate) enum BuildAddress {
     PubkeyHash(PubkeyHashArgs),
     /// It follows the same unlocking methods used by Ethereum.
     Ethereum(EthereumArgs),
-    // /// It follows the same unlocking methods used by EOS.
-    // Eos,
-    // /// It follows the same unlocking methods used by Tron.
-    // Tron,
-    // /// It follows the same unlocking methods used by Bitcoin
-    // Bitcoin,
-    // ///  It follows the same unlocking methods used by Dogecoin.
-    // Dogecoin,
+    /// It follows the same unlocking methods used by EOS.
+    Eos(EosArgs),
+    /// It follows the same unlocking methods used by Tron.
+    Tron(TronArgs),
+    /// It follows the same unlocking methods used by Bitcoin
+    Bitcoin(BitcoinArgs),
+    ///  It follows the same unlocking methods used by Dogecoin.
+    Dogecoin(DogecoinArgs),
     /// It follows the same unlocking method used by CKB MultiSig.
     Multisig(MultiSigArgs),
-    // /// The auth content that represents the blake160 hash of a lock script.
-    // /// The lock script will check if the current transaction contains an input cell with a matching lock script.
-    // /// Otherwise, it would return with an error. It's similar to P2SH in BTC.
-    // OwnerLock,
-    // /// The auth content that represents the blake160 hash of a preimage.
-    // /// The preimage contains exec information that is used to delegate signature verification to another script via exec.
-    // Exec,
-    // /// The auth content that represents the blake160 hash of a preimage.
-    // /// The preimage contains dynamic linking information that is used to delegate signature verification to the dynamic linking script.
-    // /// The interface described in Swappable Signature Verification Protocol Spec is used here.
-    // Dl,
-}
-
-pub(crate) fn build_omnilock_addr(cmds: BuildAddress, env: &ConfigContext) -> Result<()> {
-    match cmds {
-        BuildAddress::PubkeyHash(args) => {
-            build_pubkeyhash_addr(args, env)?;
-        }
-        BuildAddress::Ethereum(args) => {
-            build_ethereum_addr(args, env)?;
+    /// The auth content that represents the blake160 hash of a lock script.
+    /// The lock script will check if the current transaction contains an input cell with a matching lock script.
+    /// Otherwise, it would return with an error. It's similar to P2SH in BTC.
+    OwnerLock(OwnerLockArgs),
+    /// The auth content that represents the blake160 hash of a preimage.
+    /// The preimage contains exec information that is used to delegate signature verification to another script via exec.
+    Exec(ExecAddressArgs),
+    /// The auth content that represents the blake160 hash of a preimage.
+    /// The preimage contains dynamic linking information that is used to delegate signature verification to the dynamic linking script.
+    /// The interface described in Swappable Signature Verification Protocol Spec is used here.
+    Dl(DlAddressArgs),
+    /// The exec or dl auth delegating to a secp256r1 or WebAuthn verifier, to lock with a passkey.
+    /// The preimage carries the blake160 hash of the P-256 public key.
+    Passkey(PasskeyArgs),
+    /// The exec auth delegating to a BIP340 Schnorr verifier, for the Bitcoin taproot keys.
+    /// The preimage carries the blake160 hash of the x-only public key.
+    Schnorr(SchnorrArgs),
+    /// The exec or dl auth delegating to an ed25519 verifier, for the Solana keys.
+    /// The preimage carries the blake160 hash of the ed25519 public key.
+    Ed25519(Box<Ed25519Args>),
+    /// Build with the auth and the flags saved in an identity file.
+    Identity(IdentityArgs),
+    /// Build the addresses of many users, from a CSV file or the ckb-cli keystore.
+    Batch(BatchArgs),
+    /// Build the pubkey-hash or ethereum addresses of the keys derived from a BIP39 mnemonic.
+    Mnemonic(MnemonicArgs),
+}
+
+pub(crate) fn build_omnilock_addr(
+    cmds: BuildAddress,
+    save_identity: Option<PathBuf>,
+    env: &AddressEnv,
+) -> Result<()> {
+    let identity = match cmds {
+        BuildAddress::PubkeyHash(args) => build_pubkeyhash_addr(args, env)?,
+        BuildAddress::Ethereum(args) => build_ethereum_addr(args, env)?,
+        BuildAddress::Eos(args) => build_eos_addr(args, env)?,
+        BuildAddress::Tron(args) => build_tron_addr(args, env)?,
+        BuildAddress::Bitcoin(args) => build_bitcoin_addr(args, env)?,
+        BuildAddress::Dogecoin(args) => build_dogecoin_addr(args, env)?,
+        BuildAddress::Multisig(args) => build_multisig_addr(args, env)?,
+        BuildAddress::OwnerLock(args) => build_ownerlock_addr(args, env)?,
+        BuildAddress::Exec(args) => build_exec_addr(args, env)?,
+        BuildAddress::Dl(args) => build_dl_addr(args, env)?,
+        BuildAddress::Passkey(args) => build_passkey_addr(args, env)?,
+        BuildAddress::Schnorr(args) => build_schnorr_addr(args, env)?,
+        BuildAddress::Ed25519(args) => build_ed25519_addr(*args, env)?,
+        BuildAddress::Identity(args) => build_identity_addr(args, env)?,
+        BuildAddress::Batch(args) => {
+            ensure!(
+                save_identity.is_none(),
+                "--save-identity can't be used with batch"
+            );
+            ensure!(env.exports.is_empty(), "--export can't be used with batch");
+            return build_batch_addr(args, env);
         }
-        BuildAddress::Multisig(args) => {
-            build_multisig_addr(args, env)?;
+        BuildAddress::Mnemonic(args) => {
+            ensure!(
+                save_identity.is_none(),
+                "--save-identity can't be used with mnemonic"
+            );
+            return build_mnemonic_addr(args, env);
         }
     };
+    if let Some(path) = save_identity {
+        identity.save(&path)?;
+    }
     Ok(())
 }
 
-fn build_pubkeyhash_addr(args: PubkeyHashArgs, env: &ConfigContext) -> Result<()> {
+fn build_pubkeyhash_addr(args: PubkeyHashArgs, env: &AddressEnv) -> Result<IdentityFile> {
     let arg = if let Some(pubkey_hash) = args.pubkey_hash {
         pubkey_hash
     } else if let Some(address) = args.sighash_address {
-        let to_address_hash_type = address.payload().hash_type();
-        let to_address_code_hash: H256 = address
-            .payload()
-            .code_hash(Some(address.network()))
-            .unpack();
-        ensure!(
-            to_address_hash_type == ScriptHashType::Type
-                && to_address_code_hash == ckb_sdk::constants::SIGHASH_TYPE_HASH,
-            "The receiver's address must be a sighash address!"
-        );
-        H160::from_slice(&address.payload().args())?
+        sighash_lock_arg(&address)?
     } else {
         bail!("The receiver's pubkey hash or address must be provided!");
     };
     let config = OmniLockConfig::new_pubkey_hash(arg);
 
-    build_addr_with_omnilock_conf(&config, env, BTreeMap::default())
+    build_addr_with_omnilock_conf(config, None, &args.flag_args, env, BTreeMap::default())
 }
 
-fn build_ethereum_addr(args: EthereumArgs, env: &ConfigContext) -> Result<()> {
+fn build_ethereum_addr(args: EthereumArgs, env: &AddressEnv) -> Result<IdentityFile> {
     let mut extra_json = BTreeMap::new();
     let address = if let Some(address) = args.ethereum_address {
         address
@@ -173,39 +635,303 @@ fn build_ethereum_addr(args: EthereumArgs, env: &ConfigContext) -> Result<()> {
     };
     let config = OmniLockConfig::new_ethereum(address);
 
-    build_addr_with_omnilock_conf(&config, env, extra_json)
+    build_addr_with_omnilock_conf(config, None, &args.flag_args, env, extra_json)
+}
+
+fn build_eos_addr(args: EosArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let pubkey = if let Some(pubkey) = args.eos_pubkey {
+        pubkey
+    } else if let Some(privkey) = args.eos_privkey {
+        secp256k1::PublicKey::from_secret_key(&SECP256K1, &privkey)
+    } else {
+        bail!("should provide the public key or private key of the receiver");
+    };
+    let config = new_omnilock_config(IdentityFlag::Eos, blake160(&pubkey.serialize()))?;
+    let extra_json = BTreeMap::from([("eos-pubkey".to_owned(), json!(eos_pubkey_string(&pubkey)))]);
+
+    build_addr_with_omnilock_conf(config, None, &args.flag_args, env, extra_json)
+}
+
+fn build_tron_addr(args: TronArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let mut extra_json = BTreeMap::new();
+    let address = if let Some(address) = args.tron_address {
+        address
+    } else {
+        let pubkey = if let Some(str) = args.tron_pubkey {
+            secp256k1::PublicKey::parse(&str)?
+        } else if let Some(privkey) = args.tron_privkey {
+            secp256k1::PublicKey::from_secret_key(&SECP256K1, &privkey)
+        } else {
+            bail!("should provide at least one private key or public key of the receiver");
+        };
+        let addr = keccak160(Pubkey::from(pubkey).as_ref());
+        extra_json.insert("tron-address".to_owned(), json!(tron_address_string(&addr)));
+        addr
+    };
+    let config = new_omnilock_config(IdentityFlag::Tron, address)?;
+
+    build_addr_with_omnilock_conf(config, None, &args.flag_args, env, extra_json)
+}
+
+/// The P2PKH hash of a bitcoin like key, the public key is hashed in the form it is given.
+pub(crate) fn p2pkh_hash(
+    address: Option<H160>,
+    pubkey: Option<String>,
+    privkey: Option<PrivkeyWrapper>,
+) -> Result<H160> {
+    let hash = if let Some(hash) = address {
+        hash
+    } else if let Some(str) = pubkey {
+        let pubkey = secp256k1::PublicKey::parse(&str)?;
+        if strip_prefix_0x(&str).len() == 130 {
+            hash160(&pubkey.serialize_uncompressed())
+        } else {
+            hash160(&pubkey.serialize())
+        }
+    } else if let Some(privkey) = privkey {
+        hash160(&secp256k1::PublicKey::from_secret_key(&SECP256K1, &privkey).serialize())
+    } else {
+        bail!("should provide one of the address, private key or public key of the receiver");
+    };
+    Ok(hash)
+}
+
+fn build_bitcoin_addr(args: BitcoinArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let hash = p2pkh_hash(
+        args.bitcoin_address,
+        args.bitcoin_pubkey,
+        args.bitcoin_privkey,
+    )?;
+    let config = new_omnilock_config(IdentityFlag::Bitcoin, hash)?;
+
+    build_addr_with_omnilock_conf(config, None, &args.flag_args, env, BTreeMap::default())
+}
+
+fn build_dogecoin_addr(args: DogecoinArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let hash = p2pkh_hash(
+        args.dogecoin_address,
+        args.dogecoin_pubkey,
+        args.dogecoin_privkey,
+    )?;
+    let config = new_omnilock_config(IdentityFlag::Dogecoin, hash)?;
+
+    build_addr_with_omnilock_conf(config, None, &args.flag_args, env, BTreeMap::default())
 }
 
-fn build_multisig_addr(args: MultiSigArgs, env: &ConfigContext) -> Result<()> {
-    let multisig_config =
-        build_multisig_config(&args.sighash_address, args.require_first_n, args.threshold)?;
+fn build_multisig_addr(args: MultiSigArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let multisig_config = build_multisig_config(
+        &args.member_args.members,
+        args.require_first_n,
+        args.threshold,
+    )?;
 
     let config = OmniLockConfig::new_multisig(multisig_config);
-    build_addr_with_omnilock_conf(&config, env, BTreeMap::default())
+    build_addr_with_omnilock_conf(config, None, &args.flag_args, env, BTreeMap::default())
 }
 
-fn build_addr_with_omnilock_conf(
-    config: &OmniLockConfig,
-    env: &ConfigContext,
-    extra_json: BTreeMap<String, Value>,
-) -> Result<()> {
-    let cell = build_omnilock_cell_dep(
-        env.ckb_rpc.as_str(),
-        &env.omnilock_tx_hash,
-        env.omnilock_index,
+fn build_ownerlock_addr(args: OwnerLockArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let owner_lock = if let Some(script) = args.owner_lock {
+        script
+    } else if let Some(address) = args.owner_address {
+        Script::from(&address)
+    } else {
+        bail!("should provide the owner's address or lock script");
+    };
+    let owner_lock_hash = owner_lock.calc_script_hash();
+    let config =
+        OmniLockConfig::new_ownerlock(H160::from_slice(&owner_lock_hash.as_slice()[0..20])?);
+    let extra_json = BTreeMap::from([(
+        "owner-lock-hash".to_owned(),
+        json!(format!("{:#x}", owner_lock_hash)),
+    )]);
+
+    build_addr_with_omnilock_conf(config, None, &args.flag_args, env, extra_json)
+}
+
+fn build_exec_addr(args: ExecAddressArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let preimage = args.exec_args.preimage();
+    let config = new_omnilock_config(IdentityFlag::Exec, blake160(&preimage))?;
+    let identity = build_addr_with_omnilock_conf(
+        config,
+        Some(preimage),
+        &args.flag_args,
+        env,
+        BTreeMap::default(),
     )?;
+    Ok(identity.with_algorithm(args.exec_args.algorithm()))
+}
+
+fn build_dl_addr(args: DlAddressArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let preimage = args.dl_args.preimage();
+    let config = new_omnilock_config(IdentityFlag::Dl, blake160(&preimage))?;
+    let identity = build_addr_with_omnilock_conf(
+        config,
+        Some(preimage),
+        &args.flag_args,
+        env,
+        BTreeMap::default(),
+    )?;
+    Ok(identity.with_algorithm(args.dl_args.algorithm()))
+}
 
-    let address_payload = {
-        let args = config.build_args();
-        ckb_sdk::AddressPayload::new_full(ScriptHashType::Type, cell.type_hash.pack(), args)
+fn build_passkey_addr(args: PasskeyArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let pubkey = match (args.p256_pubkey, args.p256_key.as_ref()) {
+        (Some(pubkey), _) => pubkey,
+        (None, Some(key)) => *key.verifying_key(),
+        (None, None) => bail!("must provide the P-256 public key or private key"),
     };
-    let lock_script = Script::from(&address_payload);
-    let mut resp = serde_json::json!({
-        "mainnet": Address::new(NetworkType::Mainnet, address_payload.clone(), true).to_string(),
-        "testnet": Address::new(NetworkType::Testnet, address_payload.clone(), true).to_string(),
-        "lock-arg": format!("0x{}", hex_string(address_payload.args().as_ref())),
-        "lock-hash": format!("{:#x}", lock_script.calc_script_hash())
-    });
+    let algorithm = DelegateAlgorithm::from(args.algorithm);
+    let pubkey_hash = p256_pubkey_hash(&pubkey);
+    let (flag, preimage) = args.verifier_args.preimage(&pubkey_hash);
+    let config = new_omnilock_config(flag, blake160(&preimage))?;
+    let extra_json = BTreeMap::from([
+        ("algorithm".to_owned(), json!(algorithm.name())),
+        (
+            "p256-pubkey".to_owned(),
+            json!(format!("0x{}", hex_string(&p256_pubkey_bytes(&pubkey)))),
+        ),
+        (
+            "p256-pubkey-hash".to_owned(),
+            json!(format!("{:#x}", pubkey_hash)),
+        ),
+    ]);
+    let identity =
+        build_addr_with_omnilock_conf(config, Some(preimage), &args.flag_args, env, extra_json)?;
+    Ok(identity.with_algorithm(algorithm))
+}
+
+fn build_schnorr_addr(args: SchnorrArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let pubkey_hash = blake160(&args.xonly_pubkey.serialize());
+    let preimage = args.code_args.preimage(&pubkey_hash);
+    let config = new_omnilock_config(IdentityFlag::Exec, blake160(&preimage))?;
+    let extra_json = BTreeMap::from([
+        (
+            "algorithm".to_owned(),
+            json!(DelegateAlgorithm::Schnorr.name()),
+        ),
+        (
+            "pubkey-hash".to_owned(),
+            json!(format!("{:#x}", pubkey_hash)),
+        ),
+    ]);
+    let identity =
+        build_addr_with_omnilock_conf(config, Some(preimage), &args.flag_args, env, extra_json)?;
+    Ok(identity.with_algorithm(DelegateAlgorithm::Schnorr))
+}
+
+fn build_ed25519_addr(args: Ed25519Args, env: &AddressEnv) -> Result<IdentityFile> {
+    let pubkey = args
+        .ed25519_pubkey
+        .or(args.solana_address)
+        .ok_or_else(|| anyhow!("must provide the ed25519 public key or the Solana address"))?;
+    let pubkey_hash = blake160(pubkey.as_bytes());
+    let (flag, preimage) = args.verifier_args.preimage(&pubkey_hash);
+    let config = new_omnilock_config(flag, blake160(&preimage))?;
+    let extra_json = BTreeMap::from([
+        (
+            "algorithm".to_owned(),
+            json!(DelegateAlgorithm::Ed25519.name()),
+        ),
+        (
+            "ed25519-pubkey".to_owned(),
+            json!(format!("0x{}", hex_string(pubkey.as_bytes()))),
+        ),
+        (
+            "solana-address".to_owned(),
+            json!(base58::encode_slice(pubkey.as_bytes())),
+        ),
+        (
+            "pubkey-hash".to_owned(),
+            json!(format!("{:#x}", pubkey_hash)),
+        ),
+    ]);
+    let identity =
+        build_addr_with_omnilock_conf(config, Some(preimage), &args.flag_args, env, extra_json)?;
+    Ok(identity.with_algorithm(DelegateAlgorithm::Ed25519))
+}
+
+fn build_identity_addr(args: IdentityArgs, env: &AddressEnv) -> Result<IdentityFile> {
+    let identity = IdentityFile::load(&args.identity)?;
+    let new_identity = build_addr_with_omnilock_conf(
+        identity.auth_config()?,
+        identity.preimage(),
+        &identity.flag_args()?,
+        env,
+        BTreeMap::default(),
+    )?;
+    Ok(new_identity.with_algorithm(identity.algorithm()))
+}
+
+fn build_mnemonic_addr(args: MnemonicArgs, env: &AddressEnv) -> Result<()> {
+    let is_ethereum = args.auth == "ethereum";
+    let template = match args.derivation_path.as_ref() {
+        Some(path) => PathTemplate::new(path)?,
+        None if is_ethereum => PathTemplate::new(ETHEREUM_PATH_TEMPLATE)?,
+        None => PathTemplate::new(CKB_PATH_TEMPLATE)?,
+    };
+    ensure!(
+        template.has_index() || args.count == 1,
+        "the derivation path should have the `i` level to derive more than one key"
+    );
+    let end_index = args
+        .start_index
+        .checked_add(args.count)
+        .ok_or_else(|| anyhow!("the index is out of range"))?;
+    let seed = load_seed(&args.mnemonic_file, args.mnemonic_passphrase)?;
+    let info = env.config.omnilock_info()?;
+    let network = address_network(env)?;
+    let mut resp = Vec::new();
+    for index in args.start_index..end_index {
+        let path = template.path(index)?;
+        let privkey = derive_privkey(seed.as_ref(), &path)?;
+        let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, &privkey);
+        let mut config = if is_ethereum {
+            OmniLockConfig::new_ethereum(keccak160(Pubkey::from(pubkey).as_ref()))
+        } else {
+            OmniLockConfig::new_pubkey_hash(blake160(&pubkey.serialize()))
+        };
+        args.flag_args.apply(&mut config)?;
+        let mut item =
+            serde_json::to_value(OmniLockAddress::new(&config, &info.script_id, network))?;
+        if let Value::Object(ref mut map) = item {
+            map.extend(export_address(&info, &config, &env.exports));
+            map.insert("path".to_owned(), json!(path.to_string()));
+            let key = if is_ethereum {
+                "ethereum-address"
+            } else {
+                "pubkey-hash"
+            };
+            map.insert(
+                key.to_owned(),
+                json!(format!("{:#x}", config.id().auth_content())),
+            );
+        }
+        resp.push(item);
+    }
+
+    println!("{}", serde_json::to_string_pretty(&resp)?);
+    Ok(())
+}
+
+fn build_addr_with_omnilock_conf(
+    mut config: OmniLockConfig,
+    preimage: Option<Bytes>,
+    flag_args: &OmniLockFlagArgs,
+    env: &AddressEnv,
+    mut extra_json: BTreeMap<String, Value>,
+) -> Result<IdentityFile> {
+    flag_args.apply(&mut config)?;
+    if let Some(preimage) = preimage.as_ref() {
+        extra_json.insert(
+            "preimage".to_owned(),
+            json!(format!("0x{}", hex_string(preimage))),
+        );
+    }
+    extra_json.extend(flags_details(&config));
+    let info = env.config.omnilock_info()?;
+    extra_json.extend(export_address(&info, &config, &env.exports));
+    let network = address_network(env)?;
+    let mut resp = serde_json::to_value(OmniLockAddress::new(&config, &info.script_id, network))?;
 
     if !extra_json.is_empty() {
         if let &mut Value::Object(ref mut map) = &mut resp {
@@ -214,30 +940,128 @@ fn build_addr_with_omnilock_conf(
     }
 
     println!("{}", serde_json::to_string_pretty(&resp)?);
-    Ok(())
+    Ok(IdentityFile::new(&config, preimage.as_ref()))
+}
+
+/// The network to print the addresses for, `None` for both the mainnet and the testnet. The
+/// node is asked for its chain, unless the omnilock cell is a known deployment. The error of the
+/// node is only tolerated, with a warning, when the code hash is in the config.
+pub(crate) fn address_network(env: &AddressEnv) -> Result<Option<NetworkType>> {
+    if env.all_networks {
+        return Ok(None);
+    }
+    if let Some(deployment) = env.config.known_deployment() {
+        return Ok(Some(deployment.network));
+    }
+    let mut ckb_client = CkbRpcClient::new(env.config.ckb_rpc.as_str());
+    match get_network_type(&mut ckb_client) {
+        Ok(network) => Ok(Some(network)),
+        // Built without the node from the code hash in the config, print both networks and
+        // tell why, so a misconfigured node is not mistaken for an offline build
+        Err(err) if env.config.omnilock_script_id.is_some() => {
+            eprintln!(
+                "Warning: the network is unknown, both the mainnet and the testnet addresses are printed: {}: {}",
+                err,
+                err.root_cause()
+            );
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// The config and the output options shared by the build-address sub commands.
+pub(crate) struct AddressEnv<'a> {
+    pub config: &'a ConfigContext,
+    /// Print the addresses of both the mainnet and the testnet
+    pub all_networks: bool,
+    /// Print the lock script and the cell dep in the formats too
+    pub exports: Vec<ExportFormat>,
 }
 
+/// The addresses and the lock script of an omnilock config.
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct OmniLockAddress {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mainnet: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub testnet: Option<String>,
+    pub lock_arg: String,
+    pub lock_hash: String,
+}
+
+impl OmniLockAddress {
+    /// The addresses of the network, or of both the mainnet and the testnet if it's `None`. The
+    /// staging and dev chains share the testnet address.
+    pub(crate) fn new(
+        config: &OmniLockConfig,
+        script_id: &ScriptId,
+        network: Option<NetworkType>,
+    ) -> Self {
+        let address_payload = {
+            let args = config.build_args();
+            ckb_sdk::AddressPayload::new_full(script_id.hash_type, script_id.code_hash.pack(), args)
+        };
+        let lock_script = Script::from(&address_payload);
+        let is_mainnet = network.map(|network| network == NetworkType::Mainnet);
+        OmniLockAddress {
+            mainnet: (is_mainnet != Some(false)).then(|| {
+                Address::new(NetworkType::Mainnet, address_payload.clone(), true).to_string()
+            }),
+            testnet: (is_mainnet != Some(true)).then(|| {
+                Address::new(NetworkType::Testnet, address_payload.clone(), true).to_string()
+            }),
+            lock_arg: format!("0x{}", hex_string(address_payload.args().as_ref())),
+            lock_hash: format!("{:#x}", lock_script.calc_script_hash()),
+        }
+    }
+}
+
+/// Build the multisig config with the members' pubkey hashes, in the given order.
 pub fn build_multisig_config(
-    sighash_address: &[Address],
+    members: &[H160],
     require_first_n: u8,
     threshold: u8,
 ) -> Result<MultisigConfig> {
     ensure!(
-        !sighash_address.is_empty(),
-        "Must have at least one sighash_address"
+        !members.is_empty(),
+        "Must have at least one multisig member"
     );
-    let mut sighash_addresses = Vec::with_capacity(sighash_address.len());
-    for addr in sighash_address {
-        let lock_args = addr.payload().args();
-        if addr.payload().code_hash(None).as_slice() != SIGHASH_TYPE_HASH.as_bytes()
-            || addr.payload().hash_type() != ScriptHashType::Type
-            || lock_args.len() != 20
-        {
-            bail!("sighash_address {} is not a valid sighash address", addr);
-        }
-        sighash_addresses.push(H160::from_slice(lock_args.as_ref()).unwrap());
-    }
-
-    MultisigConfig::new_with(sighash_addresses, require_first_n, threshold)
+    MultisigConfig::new_with(members.to_vec(), require_first_n, threshold)
         .map_err(|e| anyhow!(e.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multisig_members_in_given_order() {
+        let pubkey = "0x0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let cmd = MultisigMemberArgs::augment_args(Command::new("multisig"));
+        let matches = cmd
+            .try_get_matches_from([
+                "multisig",
+                "--pubkey-hash",
+                "0xc8328aabcd9b9e8e64fbc566c4385c3bdeb219d7",
+                "--sighash-address",
+                "ckt1qyqt8xpk328d89zgl928nsgh3lelch33vvvq5u3024",
+                "--pubkey",
+                pubkey,
+                "--pubkey-hash",
+                "0x1111111111111111111111111111111111111111",
+            ])
+            .unwrap();
+        let args = MultisigMemberArgs::from_arg_matches(&matches).unwrap();
+        assert_eq!(
+            args.members,
+            vec![
+                H160::parse("0xc8328aabcd9b9e8e64fbc566c4385c3bdeb219d7").unwrap(),
+                H160::parse("0xb398368a8ed39448f95479c1178ff3fc5e316318").unwrap(),
+                PubkeyBlake160Parser::parse(pubkey).unwrap(),
+                H160([0x11; 20]),
+            ]
+        );
+    }
+}
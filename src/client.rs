@@ -1,6 +1,8 @@
-use anyhow::{ensure, Context, Result};
-use ckb_sdk::{CkbRpcClient, ScriptId};
+use anyhow::{anyhow, ensure, Context, Result};
+use ckb_hash::blake2b_256;
+use ckb_sdk::{CkbRpcClient, NetworkType, ScriptId};
 use ckb_types::{
+    core::ScriptHashType,
     packed::{Byte32, CellDep, OutPoint, Script},
     prelude::*,
     H256,
@@ -8,11 +10,21 @@ use ckb_types::{
 
 #[allow(dead_code)]
 pub struct OmniLockInfo {
-    pub type_hash: H256,
     pub script_id: ScriptId,
     pub cell_dep: CellDep,
 }
 
+impl OmniLockInfo {
+    /// The info of the omnilock cell whose script id is known without asking the node.
+    pub fn from_script_id(tx_hash: &H256, index: u32, script_id: ScriptId) -> OmniLockInfo {
+        let out_point = OutPoint::new(tx_hash.pack(), index);
+        OmniLockInfo {
+            script_id,
+            cell_dep: CellDep::new_builder().out_point(out_point).build(),
+        }
+    }
+}
+
 pub fn build_omnilock_cell_dep_from_client(
     ckb_client: &mut CkbRpcClient,
     tx_hash: &H256,
@@ -20,7 +32,7 @@ pub fn build_omnilock_cell_dep_from_client(
 ) -> Result<OmniLockInfo> {
     let out_point_json = ckb_jsonrpc_types::OutPoint {
         tx_hash: tx_hash.clone(),
-        index: ckb_jsonrpc_types::Uint32::from(index as u32),
+        index: ckb_jsonrpc_types::Uint32::from(index),
     };
     let cell_status = ckb_client
         .get_live_cell(out_point_json, false)
@@ -34,16 +46,63 @@ pub fn build_omnilock_cell_dep_from_client(
     let script = Script::from(cell_status.cell.unwrap().output.type_.unwrap());
 
     let type_hash = script.calc_script_hash();
-    let out_point = OutPoint::new(Byte32::from_slice(tx_hash.as_bytes())?, index as u32);
+    let out_point = OutPoint::new(Byte32::from_slice(tx_hash.as_bytes())?, index);
 
     let cell_dep = CellDep::new_builder().out_point(out_point).build();
     Ok(OmniLockInfo {
-        type_hash: H256::from_slice(type_hash.as_slice())?,
         script_id: ScriptId::new_type(type_hash.unpack()),
         cell_dep,
     })
 }
 
+/// Get the code hash of the script whose code is in the specified cell, according to the hash type.
+pub fn get_code_hash_from_client(
+    ckb_client: &mut CkbRpcClient,
+    tx_hash: &H256,
+    index: u32,
+    hash_type: ScriptHashType,
+) -> Result<H256> {
+    let out_point_json = ckb_jsonrpc_types::OutPoint {
+        tx_hash: tx_hash.clone(),
+        index: ckb_jsonrpc_types::Uint32::from(index),
+    };
+    let cell_status = ckb_client
+        .get_live_cell(out_point_json, true)
+        .with_context(|| "while try to load live cells".to_string())?;
+    let cell = cell_status.cell.ok_or_else(|| {
+        anyhow!(
+            "Can't find the specified script code cell: tx_hash {}, index {}",
+            tx_hash,
+            index
+        )
+    })?;
+    let code_hash = match hash_type {
+        ScriptHashType::Type => {
+            let script = cell
+                .output
+                .type_
+                .ok_or_else(|| anyhow!("The script code cell does not have a type script"))?;
+            Script::from(script).calc_script_hash().unpack()
+        }
+        _ => {
+            let data = cell
+                .data
+                .ok_or_else(|| anyhow!("The script code cell does not have data"))?;
+            H256::from(blake2b_256(data.content.as_bytes()))
+        }
+    };
+    Ok(code_hash)
+}
+
+/// Get the network of the node from the chain name in the blockchain info, the chains not known
+/// to ckb-sdk are dev chains.
+pub fn get_network_type(ckb_client: &mut CkbRpcClient) -> Result<NetworkType> {
+    let chain_info = ckb_client
+        .get_blockchain_info()
+        .with_context(|| "while try to get the blockchain info".to_string())?;
+    Ok(NetworkType::from_raw_str(&chain_info.chain).unwrap_or(NetworkType::Dev))
+}
+
 pub fn build_omnilock_cell_dep(uri: &str, tx_hash: &H256, index: u32) -> Result<OmniLockInfo> {
     let mut ckb_client = CkbRpcClient::new(uri);
     build_omnilock_cell_dep_from_client(&mut ckb_client, tx_hash, index)
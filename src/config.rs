@@ -1,11 +1,18 @@
 use std::{fs, path::PathBuf, str::FromStr};
 
 use anyhow::{anyhow, ensure, Context, Result};
-use ckb_types::H256;
+use ckb_jsonrpc_types as json_types;
+use ckb_sdk::{CkbRpcClient, NetworkType, ScriptId};
+use ckb_types::{core::ScriptHashType, h256, H256};
 use clap::Subcommand;
 use yaml_rust::YamlLoader;
 
-use crate::{client::build_omnilock_cell_dep, util::strip_prefix_0x};
+use crate::{
+    arg_parser::{ArgParser, HashTypeParser},
+    client::{build_omnilock_cell_dep, get_code_hash_from_client, OmniLockInfo},
+    export::{export_deployment, ExportFormat},
+    util::strip_prefix_0x,
+};
 
 #[derive(Subcommand)]
 pub(crate) enum ConfigCmds {
@@ -13,6 +20,19 @@ pub(crate) enum ConfigCmds {
     Init,
     /// Check if the configure file correct
     Check,
+    /// Print the omnilock deployment in the formats of the other CKB tools
+    Export {
+        /// The formats to print, separated by comma
+        #[clap(
+            long,
+            value_name = "FORMAT",
+            value_enum,
+            use_value_delimiter = true,
+            multiple_occurrences = true,
+            required = true
+        )]
+        format: Vec<ExportFormat>,
+    },
 }
 pub(crate) fn handle_config_cmds(cmds: &ConfigCmds, path: &str) -> Result<()> {
     match cmds {
@@ -27,6 +47,11 @@ pub(crate) fn handle_config_cmds(cmds: &ConfigCmds, path: &str) -> Result<()> {
         ConfigCmds::Check => {
             ConfigContext::check(path)?;
         }
+        ConfigCmds::Export { format } => {
+            let info = ConfigContext::parse(path)?.omnilock_info()?;
+            let resp = export_deployment(&info, format);
+            println!("{}", serde_json::to_string_pretty(&resp)?);
+        }
     };
     Ok(())
 }
@@ -39,6 +64,44 @@ pub struct ConfigContext {
 
     /// CKB rpc url
     pub ckb_rpc: String,
+
+    /// The code hash and the hash type of the omnilock script, if they are given in the config
+    pub omnilock_script_id: Option<ScriptId>,
+}
+
+/// An omnilock deployment on a public network.
+pub struct KnownDeployment {
+    pub network: NetworkType,
+    pub tx_hash: H256,
+    pub index: u32,
+    pub code_hash: H256,
+    pub hash_type: ScriptHashType,
+}
+
+impl KnownDeployment {
+    pub fn script_id(&self) -> ScriptId {
+        ScriptId::new(self.code_hash.clone(), self.hash_type)
+    }
+}
+
+/// The omnilock deployments on the mainnet and the testnet, see RFC 42.
+pub fn known_deployments() -> [KnownDeployment; 2] {
+    [
+        KnownDeployment {
+            network: NetworkType::Mainnet,
+            tx_hash: h256!("0xc76edf469816aa22f416503c38d0b533d2a018e253e379f134c3985b3472c842"),
+            index: 0,
+            code_hash: h256!("0x9b819793a64463aed77c615d6cb226eea5487ccfc0783043a587254cda2b6f26"),
+            hash_type: ScriptHashType::Type,
+        },
+        KnownDeployment {
+            network: NetworkType::Testnet,
+            tx_hash: h256!("0x27b62d8be8ed80b9f56ee0fe41355becdb6f6a40aeba82d3900434f43b1c8b60"),
+            index: 0,
+            code_hash: h256!("0xf329effd1c475a2978453c8600e1eaf0bc2087ee093c3ee64cc96ec6847752cb"),
+            hash_type: ScriptHashType::Type,
+        },
+    ]
 }
 
 macro_rules! try_str {
@@ -81,7 +144,41 @@ impl ConfigContext {
             omnilock_tx_hash,
             omnilock_index,
             ckb_rpc,
+            omnilock_script_id: None,
+        }
+    }
+
+    /// The known deployment of the configured omnilock cell, if it's a public one.
+    pub fn known_deployment(&self) -> Option<KnownDeployment> {
+        known_deployments().into_iter().find(|deployment| {
+            deployment.tx_hash == self.omnilock_tx_hash && deployment.index == self.omnilock_index
+        })
+    }
+
+    /// The omnilock script id without asking the node: the one in the config, or the one of the
+    /// known deployment.
+    pub fn offline_script_id(&self) -> Option<ScriptId> {
+        self.omnilock_script_id.clone().or_else(|| {
+            self.known_deployment()
+                .map(|deployment| deployment.script_id())
+        })
+    }
+
+    /// The omnilock script id and cell dep. The node is only asked when the config doesn't give
+    /// the code hash and the omnilock cell is not a known deployment.
+    pub fn omnilock_info(&self) -> Result<OmniLockInfo> {
+        if let Some(script_id) = self.offline_script_id() {
+            return Ok(OmniLockInfo::from_script_id(
+                &self.omnilock_tx_hash,
+                self.omnilock_index,
+                script_id,
+            ));
         }
+        build_omnilock_cell_dep(
+            self.ckb_rpc.as_str(),
+            &self.omnilock_tx_hash,
+            self.omnilock_index,
+        )
     }
 
     pub fn parse(path: &str) -> Result<Self> {
@@ -103,11 +200,22 @@ impl ConfigContext {
         let omnilock_index = try_i64!("omnilock_index", doc);
         let omnilock_index = u32::try_from(omnilock_index)?;
         let ckb_rpc = try_str!("ckb_rpc", doc);
-        Ok(Self::new(
-            omnilock_tx_hash,
-            omnilock_index,
-            ckb_rpc.to_string(),
-        ))
+        let mut config = Self::new(omnilock_tx_hash, omnilock_index, ckb_rpc.to_string());
+        if let Some(code_hash) = doc["omnilock_code_hash"].as_str() {
+            let code_hash = H256::parse(code_hash)
+                .with_context(|| "Fail to parse omnilock_code_hash".to_string())?;
+            let hash_type = match doc["omnilock_hash_type"].as_str() {
+                Some(hash_type) => HashTypeParser::parse(hash_type)?,
+                None => ScriptHashType::Type,
+            };
+            config.omnilock_script_id = Some(ScriptId::new(code_hash, hash_type));
+        } else {
+            ensure!(
+                doc["omnilock_hash_type"].is_badvalue(),
+                "omnilock_hash_type should be given with omnilock_code_hash"
+            );
+        }
+        Ok(config)
     }
 
     pub fn write_template(file_path: &str) -> Result<()> {
@@ -124,12 +232,54 @@ impl ConfigContext {
     pub fn check(path: &str) -> Result<()> {
         let env = Self::parse(path)?;
 
-        build_omnilock_cell_dep(
+        if let (Some(script_id), Some(deployment)) =
+            (env.omnilock_script_id.as_ref(), env.known_deployment())
+        {
+            ensure!(
+                *script_id == deployment.script_id(),
+                "the omnilock code hash and hash type do not match the known {:?} deployment: {:#x}, {}",
+                deployment.network,
+                deployment.code_hash,
+                hash_type_name(deployment.hash_type)
+            );
+        }
+        let offline_script_id = env.offline_script_id();
+        match build_omnilock_cell_dep(
             env.ckb_rpc.as_str(),
             &env.omnilock_tx_hash,
             env.omnilock_index,
-        )?;
+        ) {
+            Ok(_) => {
+                if let Some(script_id) = offline_script_id {
+                    let mut ckb_client = CkbRpcClient::new(env.ckb_rpc.as_str());
+                    let code_hash = get_code_hash_from_client(
+                        &mut ckb_client,
+                        &env.omnilock_tx_hash,
+                        env.omnilock_index,
+                        script_id.hash_type,
+                    )?;
+                    ensure!(
+                        code_hash == script_id.code_hash,
+                        "the omnilock code hash {:#x} does not match the deployed cell, whose {} hash is {:#x}",
+                        script_id.code_hash,
+                        hash_type_name(script_id.hash_type),
+                        code_hash
+                    );
+                }
+            }
+            Err(err) if offline_script_id.is_some() => {
+                println!(
+                    "the omnilock code hash is not cross-checked, the node is not available: {:#}",
+                    err
+                );
+            }
+            Err(err) => return Err(err),
+        }
         println!("the configure file `{0}` is ok!", path);
         Ok(())
     }
 }
+
+fn hash_type_name(hash_type: ScriptHashType) -> String {
+    json_types::ScriptHashType::from(hash_type).to_string()
+}
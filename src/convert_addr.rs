@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, bail, ensure, Result};
+use ckb_crypto::secp::Pubkey;
+use ckb_sdk::{
+    unlock::{IdentityFlag, OmniLockConfig},
+    util::{blake160, keccak160},
+    Address, AddressPayload,
+};
+use ckb_types::{core::ScriptHashType, packed::Script, prelude::*, H256};
+use clap::Args;
+use serde_json::{json, Value};
+
+use crate::{
+    arg_parser::{sighash_lock_arg, ArgParser},
+    config::ConfigContext,
+    export::omnilock_script,
+    flags::{flags_details, omnilock_config_from_args, OmniLockFlagArgs},
+    identity::auth_flag_name,
+};
+
+#[derive(Args)]
+pub(crate) struct ConvertAddressArgs {
+    /// The sighash address to convert to the pubkey-hash omnilock address, or the pubkey-hash or ethereum omnilock address to convert to the sighash address
+    #[clap(long, value_name = "ADDRESS")]
+    address: Address,
+
+    /// The public key (hex string) of an ethereum omnilock address, the sighash address can't be recovered from the ethereum address only
+    #[clap(long, value_name = "PUB_KEY")]
+    pubkey: Option<String>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+/// Convert between the sighash address and the omnilock address of the same key, on the network
+/// of the given address.
+pub(crate) fn convert_omnilock_addr(args: &ConvertAddressArgs, env: &ConfigContext) -> Result<()> {
+    let address = &args.address;
+    let network = address.network();
+    let info = env.omnilock_info()?;
+    let mut resp = BTreeMap::new();
+    if let Ok(lock_arg) = sighash_lock_arg(address) {
+        let mut config = OmniLockConfig::new_pubkey_hash(lock_arg);
+        args.flag_args.apply(&mut config)?;
+        let script = omnilock_script(&info, &config);
+        let omnilock_address = Address::new(network, AddressPayload::from(script.clone()), true);
+        resp.insert("sighash-address".to_owned(), json!(address.to_string()));
+        resp.insert(
+            "omnilock-address".to_owned(),
+            json!(omnilock_address.to_string()),
+        );
+        resp.insert(
+            "lock-arg".to_owned(),
+            json!(format!("0x{}", hex_string(config.build_args().as_ref()))),
+        );
+        resp.insert(
+            "lock-hash".to_owned(),
+            json!(format!("{:#x}", script.calc_script_hash())),
+        );
+    } else {
+        ensure!(
+            !args.flag_args.is_set(),
+            "the mode options only work with a sighash address"
+        );
+        let script = Script::from(address);
+        let code_hash: H256 = script.code_hash().unpack();
+        let hash_type =
+            ScriptHashType::try_from(script.hash_type()).map_err(|err| anyhow!("{}", err))?;
+        ensure!(
+            code_hash == info.script_id.code_hash && hash_type == info.script_id.hash_type,
+            "{} is neither a sighash address nor an omnilock address of the configured deployment",
+            address
+        );
+        let config = omnilock_config_from_args(script.args().raw_data().as_ref())?;
+        let flag = config.id().flag();
+        let auth_content = config.id().auth_content().clone();
+        let lock_arg = match flag {
+            IdentityFlag::PubkeyHash => auth_content,
+            IdentityFlag::Ethereum => {
+                let pubkey = args.pubkey.as_ref().ok_or_else(|| {
+                    anyhow!("--pubkey is needed to convert an ethereum omnilock address")
+                })?;
+                let pubkey = secp256k1::PublicKey::parse(pubkey)?;
+                ensure!(
+                    keccak160(Pubkey::from(pubkey).as_ref()) == auth_content,
+                    "the public key does not match the ethereum address {:#x}",
+                    auth_content
+                );
+                blake160(&pubkey.serialize())
+            }
+            _ => bail!(
+                "the sighash address can't be recovered from the {} omnilock address",
+                auth_flag_name(flag)
+            ),
+        };
+        let sighash_address = Address::new(
+            network,
+            AddressPayload::from_pubkey_hash(lock_arg.clone()),
+            true,
+        );
+        resp.insert("omnilock-address".to_owned(), json!(address.to_string()));
+        resp.insert("auth-flag".to_owned(), json!(auth_flag_name(flag)));
+        resp.insert(
+            "sighash-address".to_owned(),
+            json!(sighash_address.to_string()),
+        );
+        resp.insert("lock-arg".to_owned(), json!(format!("{:#x}", lock_arg)));
+        // The modes of the omnilock address are not kept by the sighash address
+        resp.extend(flags_details(&config));
+    }
+
+    println!("{}", serde_json::to_string_pretty(&Value::from_iter(resp))?);
+    Ok(())
+}
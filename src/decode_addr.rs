@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Context, Result};
+use ckb_jsonrpc_types as json_types;
+use ckb_sdk::{unlock::IdentityFlag, Address, AddressPayload, CkbRpcClient, NetworkType, ScriptId};
+use ckb_types::{bytes::Bytes, core::ScriptHashType, packed::Script, prelude::*, H160, H256};
+use clap::{ArgGroup, Args};
+use serde_json::{json, Value};
+
+use crate::{
+    arg_parser::{ArgParser, HexBytesParser},
+    client::get_code_hash_from_client,
+    config::ConfigContext,
+    flags::{flags_details, omnilock_config_from_args},
+    identity::auth_flag_name,
+};
+
+#[derive(Args)]
+#[clap(group(ArgGroup::new("input").required(true).args(&["address", "lock-arg"])))]
+pub(crate) struct DecodeAddressArgs {
+    /// The omnilock address to decode
+    #[clap(long, value_name = "ADDRESS")]
+    address: Option<Address>,
+
+    /// The omnilock lock script args to decode, in hex string
+    #[clap(long, value_name = "HEX", value_parser=HexBytesParser::parse)]
+    lock_arg: Option<Bytes>,
+}
+
+fn network_name(network: NetworkType) -> String {
+    format!("{:?}", network).to_lowercase()
+}
+
+fn sighash_address(network: NetworkType, pubkey_hash: H160) -> String {
+    Address::new(network, AddressPayload::from_pubkey_hash(pubkey_hash), true).to_string()
+}
+
+/// Whether the script is of the configured omnilock deployment. The node is only asked when the
+/// code hash of the deployment by the script's hash type is not known offline.
+fn deployment_matched(env: &ConfigContext, script_id: &ScriptId) -> Result<bool> {
+    if let Some(offline_id) = env
+        .offline_script_id()
+        .filter(|id| id.hash_type == script_id.hash_type)
+    {
+        return Ok(&offline_id == script_id);
+    }
+    let mut ckb_client = CkbRpcClient::new(env.ckb_rpc.as_str());
+    let code_hash = get_code_hash_from_client(
+        &mut ckb_client,
+        &env.omnilock_tx_hash,
+        env.omnilock_index,
+        script_id.hash_type,
+    )
+    .with_context(|| "try to load the configured omnilock deployment".to_string())?;
+    Ok(code_hash == script_id.code_hash)
+}
+
+/// Explain an omnilock address or its args, the reverse of `build-address`.
+pub(crate) fn decode_omnilock_addr(args: &DecodeAddressArgs, config_path: &str) -> Result<()> {
+    let mut resp = BTreeMap::new();
+    let lock_arg = if let Some(address) = args.address.as_ref() {
+        let script = Script::from(address);
+        let hash_type =
+            ScriptHashType::try_from(script.hash_type()).map_err(|err| anyhow!("{}", err))?;
+        let env = ConfigContext::parse(config_path)?;
+        resp.insert("network".to_owned(), json!(network_name(address.network())));
+        let script_code_hash: H256 = script.code_hash().unpack();
+        resp.insert(
+            "code-hash".to_owned(),
+            json!(format!("{:#x}", script_code_hash)),
+        );
+        resp.insert(
+            "hash-type".to_owned(),
+            json!(json_types::ScriptHashType::from(hash_type)),
+        );
+        let script_id = ScriptId::new(script_code_hash, hash_type);
+        match deployment_matched(&env, &script_id) {
+            Ok(matched) => {
+                resp.insert("deployment-matched".to_owned(), json!(matched));
+            }
+            Err(err) => eprintln!(
+                "Warning: can't tell if the address matches the configured omnilock deployment: {}: {}",
+                err,
+                err.root_cause()
+            ),
+        }
+        script.args().raw_data()
+    } else {
+        args.lock_arg.clone().unwrap()
+    };
+    let config = omnilock_config_from_args(lock_arg.as_ref())?;
+    let flag = config.id().flag();
+    let auth_content = config.id().auth_content().clone();
+    resp.insert(
+        "lock-arg".to_owned(),
+        json!(format!("0x{}", hex_string(lock_arg.as_ref()))),
+    );
+    resp.insert("auth-flag".to_owned(), json!(auth_flag_name(flag)));
+    resp.insert(
+        "auth-content".to_owned(),
+        json!(format!("{:#x}", auth_content)),
+    );
+    resp.extend(flags_details(&config));
+    match flag {
+        IdentityFlag::PubkeyHash => {
+            let address = match args.address.as_ref() {
+                Some(address) => json!(sighash_address(address.network(), auth_content)),
+                None => json!({
+                    "mainnet": sighash_address(NetworkType::Mainnet, auth_content.clone()),
+                    "testnet": sighash_address(NetworkType::Testnet, auth_content),
+                }),
+            };
+            resp.insert("sighash-address".to_owned(), address);
+        }
+        IdentityFlag::Ethereum => {
+            resp.insert(
+                "ethereum-address".to_owned(),
+                json!(format!("{:#x}", auth_content)),
+            );
+        }
+        _ => {}
+    }
+
+    println!("{}", serde_json::to_string_pretty(&Value::from_iter(resp))?);
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use ckb_jsonrpc_types as json_types;
+use ckb_sdk::unlock::OmniLockConfig;
+use ckb_types::{molecule::hex_string, packed::Script, prelude::*, H256};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+use crate::client::OmniLockInfo;
+
+/// The formats of the omnilock script and cell dep used by the other CKB tools.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ExportFormat {
+    /// The script in the JSON format of the CKB RPC: code_hash, hash_type and args
+    Script,
+    /// The script in molecule hex
+    ScriptMolecule,
+    /// The script config entry of Lumos
+    Lumos,
+    /// The cell dep in the JSON format of ckb-cli
+    CellDep,
+    /// The cell dep in molecule hex
+    CellDepMolecule,
+}
+
+/// The lock script of the omnilock config.
+pub(crate) fn omnilock_script(info: &OmniLockInfo, config: &OmniLockConfig) -> Script {
+    Script::new_builder()
+        .code_hash(info.script_id.code_hash.pack())
+        .hash_type(info.script_id.hash_type.into())
+        .args(config.build_args().pack())
+        .build()
+}
+
+fn lumos_entry(info: &OmniLockInfo) -> Value {
+    let out_point = info.cell_dep.out_point();
+    let tx_hash: H256 = out_point.tx_hash().unpack();
+    let index: u32 = out_point.index().unpack();
+    json!({
+        "CODE_HASH": format!("{:#x}", info.script_id.code_hash),
+        "HASH_TYPE": json_types::ScriptHashType::from(info.script_id.hash_type),
+        "TX_HASH": format!("{:#x}", tx_hash),
+        "INDEX": format!("{:#x}", index),
+        "DEP_TYPE": "code",
+    })
+}
+
+fn export(
+    info: &OmniLockInfo,
+    script: &Script,
+    formats: &[ExportFormat],
+) -> BTreeMap<String, Value> {
+    formats
+        .iter()
+        .map(|format| {
+            let value = match format {
+                ExportFormat::Script => json!(json_types::Script::from(script.clone())),
+                ExportFormat::ScriptMolecule => {
+                    json!(format!("0x{}", hex_string(script.as_slice())))
+                }
+                ExportFormat::Lumos => lumos_entry(info),
+                ExportFormat::CellDep => json!(json_types::CellDep::from(info.cell_dep.clone())),
+                ExportFormat::CellDepMolecule => {
+                    json!(format!("0x{}", hex_string(info.cell_dep.as_slice())))
+                }
+            };
+            let name = format
+                .to_possible_value()
+                .expect("no export format is skipped")
+                .get_name()
+                .to_owned();
+            (name, value)
+        })
+        .collect()
+}
+
+/// The lock script of the omnilock address and the cell dep of the omnilock script, as items
+/// of the JSON output.
+pub(crate) fn export_address(
+    info: &OmniLockInfo,
+    config: &OmniLockConfig,
+    formats: &[ExportFormat],
+) -> BTreeMap<String, Value> {
+    export(info, &omnilock_script(info, config), formats)
+}
+
+/// The omnilock deployment, the script has empty args.
+pub(crate) fn export_deployment(
+    info: &OmniLockInfo,
+    formats: &[ExportFormat],
+) -> BTreeMap<String, Value> {
+    let script = Script::new_builder()
+        .code_hash(info.script_id.code_hash.pack())
+        .hash_type(info.script_id.hash_type.into())
+        .build();
+    export(info, &script, formats)
+}
@@ -0,0 +1,368 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, ensure, Result};
+use ckb_sdk::{
+    unlock::{IdentityFlag, OmniLockAcpConfig, OmniLockConfig},
+    HumanCapacity,
+};
+use ckb_types::{H160, H256};
+use clap::Args;
+use serde_json::{json, Value};
+
+use crate::{
+    arg_parser::{ArgParser, SinceArgParser},
+    sdk_compat::{acp_config, new_omnilock_config, set_admin_rc_type_id, time_lock_since},
+    time_lock::since_string,
+};
+
+/// The bit of the administrator mode in the omnilock args flags.
+pub(crate) const ADMIN_FLAG: u8 = 1;
+/// The bit of the anyone-can-pay mode in the omnilock args flags.
+const ACP_FLAG: u8 = 1 << 1;
+/// The bit of the time-lock mode in the omnilock args flags.
+const TIME_LOCK_FLAG: u8 = 1 << 2;
+/// The bit of the supply mode in the omnilock args flags.
+const SUPPLY_FLAG: u8 = 1 << 3;
+
+/// The max exponent of the anyone-can-pay minimum CKB, 10^19 shannons still fits in u64.
+pub(crate) const MAX_ACP_CKB_EXP: u8 = 19;
+/// The max exponent of the anyone-can-pay minimum UDT, 10^38 still fits in u128.
+pub(crate) const MAX_ACP_UDT_EXP: u8 = 38;
+
+/// The omnilock args flags options shared by all auth types.
+#[derive(Args)]
+pub(crate) struct OmniLockFlagArgs {
+    /// Enable the administrator mode, the type ID(type script hash) of the RC cell which contains the administrators
+    #[clap(long, value_name = "TYPE_ID", value_parser=H256::parse)]
+    pub admin_rc_type_id: Option<H256>,
+
+    /// Enable the anyone-can-pay mode
+    #[clap(long)]
+    pub acp: bool,
+
+    /// Work with "--acp", the minimum CKB to transfer into the cell is 10^EXP shannons
+    #[clap(long, value_name = "EXP", default_value = "0", requires = "acp", value_parser = clap::value_parser!(u8).range(0..=MAX_ACP_CKB_EXP as i64))]
+    pub acp_min_ckb_exp: u8,
+
+    /// Work with "--acp", the minimum UDT to transfer into the cell is 10^EXP
+    #[clap(long, value_name = "EXP", default_value = "0", requires = "acp", value_parser = clap::value_parser!(u8).range(0..=MAX_ACP_UDT_EXP as i64))]
+    pub acp_min_udt_exp: u8,
+
+    /// Enable the time-lock mode, the cell can only be spent after the since: `[relative:](block|epoch|timestamp):VALUE`, the epoch is in `NUMBER[.INDEX/LENGTH]` format and the timestamp is in seconds; or a raw since value in hex
+    #[clap(long, value_name = "SINCE", value_parser=SinceArgParser::parse)]
+    pub time_lock_since: Option<u64>,
+
+    /// Enable the supply mode, the type script hash of the info cell which records the supply of the xUDT issued by the omnilock
+    #[clap(long, value_name = "HASH", value_parser=H256::parse)]
+    pub supply_info_type_hash: Option<H256>,
+}
+
+impl OmniLockFlagArgs {
+    /// Set the flags and the according args to the omnilock config.
+    pub(crate) fn apply(&self, config: &mut OmniLockConfig) -> Result<()> {
+        self.validate()?;
+        if let Some(rc_type_id) = self.admin_rc_type_id.as_ref() {
+            set_admin_rc_type_id(config, rc_type_id)?;
+        }
+        if self.acp {
+            config.set_acp_config(OmniLockAcpConfig::new(
+                self.acp_min_ckb_exp,
+                self.acp_min_udt_exp,
+            ));
+        }
+        if let Some(since) = self.time_lock_since {
+            config.set_time_lock_config(since);
+        }
+        if let Some(type_hash) = self.supply_info_type_hash.as_ref() {
+            config.set_info_cell(type_hash.clone());
+        }
+        Ok(())
+    }
+
+    /// Whether any flag is given.
+    pub(crate) fn is_set(&self) -> bool {
+        self.admin_rc_type_id.is_some()
+            || self.acp
+            || self.time_lock_since.is_some()
+            || self.supply_info_type_hash.is_some()
+    }
+
+    /// Reject the flags which lock nothing: a zero `--time-lock-since`, or an all zeros
+    /// `--admin-rc-type-id` or `--supply-info-type-hash`. Every combination of the administrator,
+    /// anyone-can-pay, time-lock and supply modes is allowed, as the omnilock spec does.
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.time_lock_since != Some(0),
+            "--time-lock-since can't be 0, which doesn't lock the cell at all"
+        );
+        for (name, hash) in [
+            ("--admin-rc-type-id", &self.admin_rc_type_id),
+            ("--supply-info-type-hash", &self.supply_info_type_hash),
+        ] {
+            ensure!(
+                hash.as_ref() != Some(&H256::default()),
+                "{} can't be all zeros",
+                name
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The breakdown of the omnilock args flags byte.
+fn flags_json(config: &OmniLockConfig) -> Value {
+    let bits = config.omni_lock_flags().bits();
+    json!({
+        "byte": format!("0x{:02x}", bits),
+        "administrator": bits & ADMIN_FLAG != 0,
+        "anyone-can-pay": bits & ACP_FLAG != 0,
+        "time-lock": bits & TIME_LOCK_FLAG != 0,
+        "supply": bits & SUPPLY_FLAG != 0,
+    })
+}
+
+/// The flags byte breakdown and the args of every enabled mode, as items of the JSON output.
+pub(crate) fn flags_details(config: &OmniLockConfig) -> BTreeMap<String, Value> {
+    let mut details = BTreeMap::new();
+    details.insert("flags".to_owned(), flags_json(config));
+    if let Some(rc_type_id) = admin_rc_type_id(config) {
+        details.insert(
+            "admin-rc-type-id".to_owned(),
+            json!(format!("{:#x}", rc_type_id)),
+        );
+    }
+    if let Some(acp) = acp_config(config) {
+        let min_ckb = HumanCapacity(10u64.pow(acp.ckb_minimum as u32));
+        let min_udt = 10u128.pow(acp.udt_minimum as u32);
+        details.insert("acp-min-ckb".to_owned(), json!(min_ckb.to_string()));
+        details.insert("acp-min-udt".to_owned(), json!(min_udt.to_string()));
+    }
+    if let Some(since) = time_lock_since(config) {
+        details.insert("time-lock-since".to_owned(), json!(since_string(since)));
+    }
+    if let Some(type_hash) = config.get_info_cell() {
+        details.insert(
+            "supply-info-type-hash".to_owned(),
+            json!(format!("{:#x}", type_hash)),
+        );
+    }
+    details
+}
+
+/// The RC cell's type ID of the administrator mode, if it's enabled.
+pub(crate) fn admin_rc_type_id(config: &OmniLockConfig) -> Option<H256> {
+    config
+        .get_admin_config()
+        .map(|admin_config| admin_config.rc_type_id().clone())
+}
+
+/// Recover the omnilock config from the lock script args. The multisig config can't be recovered
+/// from its hash, so it's only good for building the args and checking the flags.
+pub(crate) fn omnilock_config_from_args(args: &[u8]) -> Result<OmniLockConfig> {
+    ensure!(
+        args.len() >= 22,
+        "invalid omnilock args length: {}, expected not less than 22",
+        args.len()
+    );
+    let flag = IdentityFlag::try_from(args[0])
+        .map_err(|_| anyhow!("unknown omnilock auth flag 0x{:02x}", args[0]))?;
+    let mut config = new_omnilock_config(flag, H160::from_slice(&args[1..21])?)?;
+    let flags = args[21];
+    let mut offset = 22;
+    let mut take = |len: usize| -> Result<&[u8]> {
+        ensure!(
+            args.len() >= offset + len,
+            "the omnilock args is too short for the flags 0x{:02x}",
+            flags
+        );
+        offset += len;
+        Ok(&args[offset - len..offset])
+    };
+    if flags & ADMIN_FLAG != 0 {
+        set_admin_rc_type_id(&mut config, &H256::from_slice(take(32)?)?)?;
+    }
+    if flags & ACP_FLAG != 0 {
+        let acp = take(2)?;
+        ensure!(
+            acp[0] <= MAX_ACP_CKB_EXP && acp[1] <= MAX_ACP_UDT_EXP,
+            "the anyone-can-pay minimum exponents {} and {} are out of range, CKB's should not exceed {} and UDT's {}",
+            acp[0],
+            acp[1],
+            MAX_ACP_CKB_EXP,
+            MAX_ACP_UDT_EXP
+        );
+        config.set_acp_config(OmniLockAcpConfig::new(acp[0], acp[1]));
+    }
+    if flags & TIME_LOCK_FLAG != 0 {
+        let mut since = [0u8; 8];
+        since.copy_from_slice(take(8)?);
+        config.set_time_lock_config(u64::from_le_bytes(since));
+    }
+    if flags & SUPPLY_FLAG != 0 {
+        config.set_info_cell(H256::from_slice(take(32)?)?);
+    }
+    ensure!(
+        flags & !(ADMIN_FLAG | ACP_FLAG | TIME_LOCK_FLAG | SUPPLY_FLAG) == 0,
+        "unsupported omnilock args flags 0x{:02x}",
+        flags
+    );
+    ensure!(
+        offset == args.len(),
+        "unexpected trailing bytes in the omnilock args"
+    );
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ckb_sdk::{Address, AddressPayload, NetworkType};
+    use ckb_types::{core::ScriptHashType, packed::Script, prelude::*};
+    use std::str::FromStr;
+
+    fn omnilock_address(args: Vec<u8>) -> Address {
+        let code_hash = H256([0x33; 32]);
+        let payload = AddressPayload::new_full(ScriptHashType::Type, code_hash.pack(), args.into());
+        Address::new(NetworkType::Testnet, payload, true)
+    }
+
+    fn acp_args(ckb_exp: u8, udt_exp: u8) -> Vec<u8> {
+        let mut args = vec![IdentityFlag::PubkeyHash as u8];
+        args.extend_from_slice(&[0x11; 20]);
+        args.extend_from_slice(&[ACP_FLAG, ckb_exp, udt_exp]);
+        args
+    }
+
+    #[test]
+    fn test_decode_acp_exponents_out_of_range() {
+        for (ckb_exp, udt_exp) in [
+            (MAX_ACP_CKB_EXP + 1, 0),
+            (0, MAX_ACP_UDT_EXP + 1),
+            (255, 255),
+        ] {
+            let address = omnilock_address(acp_args(ckb_exp, udt_exp));
+            let args = Script::from(&address).args().raw_data();
+            let err = omnilock_config_from_args(&args).unwrap_err();
+            assert!(err.to_string().contains("out of range"), "{}", err);
+        }
+
+        let address = omnilock_address(acp_args(MAX_ACP_CKB_EXP, MAX_ACP_UDT_EXP));
+        let config = omnilock_config_from_args(&Script::from(&address).args().raw_data()).unwrap();
+        let details = flags_details(&config);
+        assert_eq!(details["acp-min-ckb"], json!("100000000000.0"));
+        assert_eq!(details["acp-min-udt"], json!(10u128.pow(38).to_string()));
+    }
+
+    fn all_flag_args() -> OmniLockFlagArgs {
+        OmniLockFlagArgs {
+            admin_rc_type_id: Some(H256([0x44; 32])),
+            acp: true,
+            acp_min_ckb_exp: 3,
+            acp_min_udt_exp: 5,
+            time_lock_since: Some(0x2000_0000_0000_0064),
+            supply_info_type_hash: Some(H256([0x55; 32])),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        // every combination of the four modes
+        for bits in 0u8..16 {
+            let all = all_flag_args();
+            let args = OmniLockFlagArgs {
+                admin_rc_type_id: all.admin_rc_type_id.filter(|_| bits & 1 != 0),
+                acp: bits & 2 != 0,
+                time_lock_since: all.time_lock_since.filter(|_| bits & 4 != 0),
+                supply_info_type_hash: all.supply_info_type_hash.filter(|_| bits & 8 != 0),
+                ..all
+            };
+            args.validate().unwrap();
+        }
+
+        for (args, name) in [
+            (
+                OmniLockFlagArgs {
+                    time_lock_since: Some(0),
+                    ..all_flag_args()
+                },
+                "--time-lock-since",
+            ),
+            (
+                OmniLockFlagArgs {
+                    admin_rc_type_id: Some(H256::default()),
+                    ..all_flag_args()
+                },
+                "--admin-rc-type-id",
+            ),
+            (
+                OmniLockFlagArgs {
+                    supply_info_type_hash: Some(H256::default()),
+                    ..all_flag_args()
+                },
+                "--supply-info-type-hash",
+            ),
+        ] {
+            let err = args.validate().unwrap_err();
+            assert!(err.to_string().starts_with(name), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_omnilock_config_address_round_trip() {
+        let mut config = OmniLockConfig::new_ethereum(H160([0x11; 20]));
+        all_flag_args().apply(&mut config).unwrap();
+        let address = omnilock_address(config.build_args().to_vec());
+
+        let decoded = Address::from_str(&address.to_string()).unwrap();
+        let args = Script::from(&decoded).args().raw_data();
+        let decoded_config = omnilock_config_from_args(&args).unwrap();
+        assert_eq!(decoded_config.id().flag(), IdentityFlag::Ethereum);
+        assert_eq!(decoded_config.id().auth_content(), &H160([0x11; 20]));
+        assert_eq!(decoded_config.build_args(), config.build_args());
+        assert_eq!(flags_details(&decoded_config), flags_details(&config));
+    }
+
+    #[test]
+    fn test_flags_details() {
+        let config = OmniLockConfig::new_pubkey_hash(H160([0x11; 20]));
+        assert_eq!(
+            flags_json(&config),
+            json!({
+                "byte": "0x00",
+                "administrator": false,
+                "anyone-can-pay": false,
+                "time-lock": false,
+                "supply": false,
+            })
+        );
+        assert_eq!(flags_details(&config).len(), 1);
+
+        let mut config = config;
+        all_flag_args().apply(&mut config).unwrap();
+        let details = flags_details(&config);
+        assert_eq!(
+            details["flags"],
+            json!({
+                "byte": "0x0f",
+                "administrator": true,
+                "anyone-can-pay": true,
+                "time-lock": true,
+                "supply": true,
+            })
+        );
+        assert_eq!(
+            details["admin-rc-type-id"],
+            json!(format!("{:#x}", H256([0x44; 32])))
+        );
+        assert_eq!(details["acp-min-ckb"], json!("0.00001"));
+        assert_eq!(details["acp-min-udt"], json!("100000"));
+        assert_eq!(
+            details["time-lock-since"],
+            json!(since_string(0x2000_0000_0000_0064))
+        );
+        assert_eq!(
+            details["supply-info-type-hash"],
+            json!(format!("{:#x}", H256([0x55; 32])))
+        );
+    }
+}
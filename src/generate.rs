@@ -1,38 +1,50 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use ckb_jsonrpc_types as json_types;
+use ckb_jsonrpc_types::JsonBytes;
 use ckb_sdk::{
+    constants::{MULTISIG_TYPE_HASH, SIGHASH_TYPE_HASH, TYPE_ID_CODE_HASH},
     rpc::CkbRpcClient,
     traits::{
-        DefaultCellCollector, DefaultCellDepResolver, DefaultHeaderDepResolver,
-        DefaultTransactionDependencyProvider, SecpCkbRawKeySigner,
+        CellCollector, CellDepResolver, CellQueryOptions, DefaultCellCollector,
+        DefaultCellDepResolver, DefaultHeaderDepResolver, DefaultTransactionDependencyProvider,
+        SecpCkbRawKeySigner, Signer, TransactionDependencyProvider, ValueRangeOption,
     },
     tx_builder::{
         balance_tx_capacity, fill_placeholder_witnesses, transfer::CapacityTransferBuilder,
         CapacityBalancer, TxBuilder,
     },
-    unlock::{OmniLockConfig, OmniLockScriptSigner},
+    unlock::{IdentityFlag, InfoCellData, MultisigConfig, OmniLockConfig, OmniLockScriptSigner},
     unlock::{OmniLockUnlocker, OmniUnlockMode, ScriptUnlocker},
+    util::blake160,
     Address, HumanCapacity, ScriptId,
 };
 use ckb_types::{
     bytes::Bytes,
-    core::{BlockView, ScriptHashType, TransactionView},
-    packed::{CellDep, CellOutput, OutPoint, Script},
+    core::{BlockView, Capacity, ScriptHashType, TransactionView},
+    packed::{self, CellDep, CellInput, CellOutput, OutPoint, Script, WitnessArgs},
     prelude::*,
     H160, H256,
 };
 use clap::{Args, Subcommand};
 
 use crate::{
-    arg_parser::{ArgParser, PrivkeyWrapper},
-    build_addr::build_multisig_config,
-    client::build_omnilock_cell_dep_from_client,
+    arg_parser::{
+        ArgParser, BitcoinAddressParser, DogecoinAddressParser, EosPubkeyParser, HashTypeParser,
+        PrivkeyWrapper, ScriptArgParser, TronAddressParser,
+    },
+    build_addr::{build_multisig_config, DlArgs, ExecArgs, MultisigMemberArgs},
+    client::{get_code_hash_from_client, OmniLockInfo},
     config::ConfigContext,
-    signer::CommonSigner,
+    export,
+    flags::{admin_rc_type_id, omnilock_config_from_args, OmniLockFlagArgs},
+    identity::IdentityFile,
+    sdk_compat::{acp_config, new_omnilock_config, time_lock_since},
+    signer::{BtcMessageSigner, CommonSigner, Ed25519Signer, EosSigner, SchnorrSigner, TronSigner},
+    time_lock::check_since_satisfied,
     txinfo::TxInfo,
+    unlock::{is_sdk_supported, placeholder_witness, DelegateAlgorithm, OmniLockSigUnlocker},
 };
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use std::fs;
 #[derive(Args)]
 pub struct GeneratePubkeyHashArgs {
@@ -57,6 +69,42 @@ pub struct CommonArgs {
     /// The output transaction info file (.json)
     #[clap(long, value_name = "PATH")]
     tx_file: PathBuf,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+
+    #[clap(flatten)]
+    rc_cell_args: RcCellArgs,
+}
+
+#[derive(Args)]
+pub struct RcCellArgs {
+    /// Work with the administrator mode, the transaction hash of the RC cell, which is added into the cell deps
+    #[clap(long, value_name = "HASH", value_parser=H256::parse, requires = "rc-cell-index")]
+    rc_cell_tx_hash: Option<H256>,
+
+    /// Work with the administrator mode, the output index of the RC cell
+    #[clap(long, value_name = "INDEX", requires = "rc-cell-tx-hash")]
+    rc_cell_index: Option<u32>,
+}
+
+impl RcCellArgs {
+    /// The cell deps of the RC cell if the omnilock is in administrator mode, or nothing.
+    fn cell_deps(
+        &self,
+        ckb_client: &mut CkbRpcClient,
+        omnilock_config: &OmniLockConfig,
+    ) -> Result<Vec<CellDep>> {
+        if let Some(rc_type_id) = admin_rc_type_id(omnilock_config) {
+            Ok(vec![build_rc_cell_dep(ckb_client, self, &rc_type_id)?])
+        } else {
+            ensure!(
+                self.rc_cell_tx_hash.is_none(),
+                "--rc-cell-tx-hash only works with the administrator mode"
+            );
+            Ok(Vec::new())
+        }
+    }
 }
 
 #[derive(Args)]
@@ -69,9 +117,8 @@ pub struct GenerateMultiSigArgs {
     #[clap(long, value_name = "NUM")]
     threshold: u8,
 
-    /// Normal sighash address
-    #[clap(long, value_name = "ADDRESS", multiple_values = true)]
-    sighash_address: Vec<Address>,
+    #[clap(flatten)]
+    member_args: MultisigMemberArgs,
 
     #[clap(flatten)]
     common_args: CommonArgs,
@@ -86,27 +133,269 @@ pub struct GenerateEthereumArgs {
     #[clap(flatten)]
     common_args: CommonArgs,
 }
+
+#[derive(Args)]
+pub struct GenerateEosArgs {
+    /// The sender's EOS public key, in `EOS...` or `PUB_K1_...` format
+    #[clap(long, value_name = "PUB_KEY", value_parser=EosPubkeyParser::parse)]
+    sender_pubkey: secp256k1::PublicKey,
+
+    #[clap(flatten)]
+    common_args: CommonArgs,
+}
+
+#[derive(Args)]
+pub struct GenerateTronArgs {
+    /// The sender's tron address
+    #[clap(long, value_name = "ADDRESS", value_parser=TronAddressParser::parse)]
+    sender_address: H160,
+
+    #[clap(flatten)]
+    common_args: CommonArgs,
+}
+
+#[derive(Args)]
+pub struct GenerateBitcoinArgs {
+    /// The sender's bitcoin address, P2PKH, P2SH-P2WPKH or P2WPKH
+    #[clap(long, value_name = "ADDRESS", value_parser=BitcoinAddressParser::parse)]
+    sender_address: H160,
+
+    #[clap(flatten)]
+    common_args: CommonArgs,
+}
+
+#[derive(Args)]
+pub struct GenerateDogecoinArgs {
+    /// The sender's dogecoin P2PKH address
+    #[clap(long, value_name = "ADDRESS", value_parser=DogecoinAddressParser::parse)]
+    sender_address: H160,
+
+    #[clap(flatten)]
+    common_args: CommonArgs,
+}
+#[derive(Args)]
+pub struct GenerateOwnerLockArgs {
+    /// The owner's sighash or multisig address, a live cell of it will be consumed to prove the ownership
+    #[clap(long, value_name = "ADDRESS")]
+    owner_address: Address,
+
+    /// Work with a multisig owner address, require first n signatures of corresponding pubkey
+    #[clap(long, value_name = "NUM")]
+    require_first_n: Option<u8>,
+
+    /// Work with a multisig owner address, multisig threshold
+    #[clap(long, value_name = "NUM")]
+    threshold: Option<u8>,
+
+    // Work with a multisig owner address, the members of the multisig config
+    #[clap(flatten)]
+    member_args: MultisigMemberArgs,
+
+    #[clap(flatten)]
+    common_args: CommonArgs,
+}
+
+#[derive(Args)]
+pub struct CellDepArgs {
+    /// The transaction hash of the cell which contains the code of the script the verification is delegated to
+    #[clap(long, value_name = "HASH", value_parser=H256::parse, requires = "cell-dep-index")]
+    cell_dep_tx_hash: Option<H256>,
+
+    /// The output index of the cell which contains the code of the script
+    #[clap(long, value_name = "INDEX", requires = "cell-dep-tx-hash")]
+    cell_dep_index: Option<u32>,
+}
+
+impl CellDepArgs {
+    fn cell_deps(&self) -> Vec<CellDep> {
+        match (&self.cell_dep_tx_hash, self.cell_dep_index) {
+            (Some(tx_hash), Some(index)) => {
+                let out_point = OutPoint::new(tx_hash.pack(), index);
+                vec![CellDep::new_builder().out_point(out_point).build()]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct GenerateExecArgs {
+    #[clap(flatten)]
+    exec_args: ExecArgs,
+
+    #[clap(flatten)]
+    cell_dep_args: CellDepArgs,
+
+    #[clap(flatten)]
+    common_args: CommonArgs,
+}
+
+#[derive(Args)]
+pub struct GenerateDlArgs {
+    #[clap(flatten)]
+    dl_args: DlArgs,
+
+    /// The transaction hash of the cell which contains the dynamic library
+    #[clap(long, value_name = "HASH", value_parser=H256::parse)]
+    library_tx_hash: H256,
+
+    /// The output index of the cell which contains the dynamic library
+    #[clap(long, value_name = "INDEX")]
+    library_index: u32,
+
+    #[clap(flatten)]
+    common_args: CommonArgs,
+}
+
+#[derive(Args)]
+pub struct GenerateIdentityArgs {
+    /// The identity file (.yaml or .json) of the sender's auth and flags
+    #[clap(long, value_name = "PATH")]
+    identity: PathBuf,
+
+    #[clap(flatten)]
+    cell_dep_args: CellDepArgs,
+
+    #[clap(flatten)]
+    common_args: CommonArgs,
+}
+
+#[derive(Args)]
+pub struct GenerateAcpDepositArgs {
+    /// The anyone-can-pay omnilock address to deposit into
+    #[clap(long, value_name = "ADDRESS")]
+    acp_address: Address,
+
+    /// The depositor's sighash address, which provides the capacity and pays the fee
+    #[clap(long, value_name = "ADDRESS")]
+    sender_address: Address,
+
+    /// The capacity to deposit (unit: CKB, example: 102.43)
+    #[clap(long, value_name = "CKB")]
+    capacity: HumanCapacity,
+
+    /// The output transaction info file (.json)
+    #[clap(long, value_name = "PATH")]
+    tx_file: PathBuf,
+}
+
+#[derive(Args)]
+pub struct GenerateIssueXudtArgs {
+    /// The issuer's omnilock address in supply mode, the xUDT's owner lock
+    #[clap(long, value_name = "ADDRESS")]
+    issuer_address: Address,
+
+    /// The type script of the info cell in JSON, its hash is the "--supply-info-type-hash" of the issuer
+    #[clap(long, value_name = "SCRIPT", value_parser=ScriptArgParser::parse)]
+    info_cell_type: Script,
+
+    /// The transaction hash of the cell which contains the code of the info cell's type script, not needed by a built-in script like type ID
+    #[clap(long, value_name = "HASH", value_parser=H256::parse, requires = "info-type-dep-index")]
+    info_type_dep_tx_hash: Option<H256>,
+
+    /// The output index of the cell which contains the code of the info cell's type script
+    #[clap(long, value_name = "INDEX", requires = "info-type-dep-tx-hash")]
+    info_type_dep_index: Option<u32>,
+
+    /// The transaction hash of the cell which contains the xUDT script
+    #[clap(long, value_name = "HASH", value_parser=H256::parse)]
+    xudt_tx_hash: H256,
+
+    /// The output index of the cell which contains the xUDT script
+    #[clap(long, value_name = "INDEX")]
+    xudt_index: u32,
+
+    /// The hash type of the xUDT script: data, type or data1
+    #[clap(long, value_name = "HASH_TYPE", default_value = "type", value_parser=HashTypeParser::parse)]
+    xudt_hash_type: ScriptHashType,
+
+    /// Create the info cell with the max supply of the xUDT instead of minting, the capacity is paid by the issuer's omnilock without any flag
+    #[clap(long, value_name = "AMOUNT", conflicts_with_all = &["receiver", "amount", "rc-cell-tx-hash"])]
+    max_supply: Option<u128>,
+
+    /// The receiver address of the minted xUDT
+    #[clap(long, value_name = "ADDRESS", required_unless_present = "max-supply")]
+    receiver: Option<Address>,
+
+    /// The amount of xUDT to mint
+    #[clap(long, value_name = "AMOUNT", required_unless_present = "max-supply")]
+    amount: Option<u128>,
+
+    /// The output transaction info file (.json)
+    #[clap(long, value_name = "PATH")]
+    tx_file: PathBuf,
+
+    #[clap(flatten)]
+    rc_cell_args: RcCellArgs,
+}
+
+/// The owner lock of an owner-lock omnilock cell, whose live cell is put into the inputs to unlock it.
+struct OwnerInput {
+    lock: Script,
+    placeholder_witness: WitnessArgs,
+    multisig_config: Option<MultisigConfig>,
+}
+
+/// What it takes to unlock the omnilock cells besides the signature.
+#[derive(Default)]
+struct UnlockExtra {
+    /// The owner's input of an owner lock cell
+    owner: Option<OwnerInput>,
+    /// The preimage of the auth content
+    preimage: Option<Bytes>,
+    /// The signature algorithm the exec script or the dynamic library verifies
+    algorithm: DelegateAlgorithm,
+    /// The cell deps of the scripts the verification is delegated to
+    cell_deps: Vec<CellDep>,
+}
+
 #[derive(Subcommand)]
 pub enum GenerateTx {
     /// to generate a transaction from pubkey hash omnilock cell
     PubkeyHash(GeneratePubkeyHashArgs),
     /// to generate a transaction from ethereum omnilock cell
     Ethereum(GenerateEthereumArgs),
+    /// to generate a transaction from EOS omnilock cell
+    Eos(GenerateEosArgs),
+    /// to generate a transaction from tron omnilock cell
+    Tron(GenerateTronArgs),
+    /// to generate a transaction from bitcoin omnilock cell
+    Bitcoin(GenerateBitcoinArgs),
+    /// to generate a transaction from dogecoin omnilock cell
+    Dogecoin(GenerateDogecoinArgs),
     /// to generate a transaction from multisig omnilock cell
     Multisig(GenerateMultiSigArgs),
+    /// to generate a transaction from owner lock omnilock cell
+    OwnerLock(GenerateOwnerLockArgs),
+    /// to generate a transaction from exec omnilock cell
+    Exec(GenerateExecArgs),
+    /// to generate a transaction from dynamic linking omnilock cell
+    Dl(GenerateDlArgs),
+    /// to generate a transaction from the omnilock cell of an identity file
+    Identity(GenerateIdentityArgs),
+    /// to generate a transaction depositing into an anyone-can-pay omnilock cell, no signature of the cell's owner is needed
+    AcpDeposit(GenerateAcpDepositArgs),
+    /// to generate a transaction creating the info cell or minting xUDT of a supply mode omnilock
+    IssueXudt(GenerateIssueXudtArgs),
 }
 
 pub fn generate_transfer_tx(cmds: &GenerateTx, env: &ConfigContext) -> Result<()> {
-    let (tx, omnilock_config, tx_file) = match cmds {
+    let (tx_info, tx_file) = match cmds {
         GenerateTx::PubkeyHash(args) => build_pubkeyhash_transfer_tx(args, env)?,
         GenerateTx::Ethereum(args) => build_ethereum_transfer_tx(args, env)?,
+        GenerateTx::Eos(args) => build_eos_transfer_tx(args, env)?,
+        GenerateTx::Tron(args) => build_tron_transfer_tx(args, env)?,
+        GenerateTx::Bitcoin(args) => build_bitcoin_transfer_tx(args, env)?,
+        GenerateTx::Dogecoin(args) => build_dogecoin_transfer_tx(args, env)?,
         GenerateTx::Multisig(args) => build_multisig_transfer_tx(args, env)?,
+        GenerateTx::OwnerLock(args) => build_ownerlock_transfer_tx(args, env)?,
+        GenerateTx::Exec(args) => build_exec_transfer_tx(args, env)?,
+        GenerateTx::Dl(args) => build_dl_transfer_tx(args, env)?,
+        GenerateTx::Identity(args) => build_identity_transfer_tx(args, env)?,
+        GenerateTx::AcpDeposit(args) => build_acp_deposit_tx(args, env)?,
+        GenerateTx::IssueXudt(args) => build_issue_xudt_tx(args, env)?,
     };
 
-    let tx_info = TxInfo {
-        transaction: json_types::TransactionView::from(tx).inner,
-        omnilock_config,
-    };
     fs::write(tx_file, serde_json::to_string_pretty(&tx_info)?)?;
     Ok(())
 }
@@ -114,47 +403,136 @@ pub fn generate_transfer_tx(cmds: &GenerateTx, env: &ConfigContext) -> Result<()
 fn build_pubkeyhash_transfer_tx(
     args: &GeneratePubkeyHashArgs,
     env: &ConfigContext,
-) -> Result<(TransactionView, OmniLockConfig, PathBuf)> {
+) -> Result<(TxInfo, PathBuf)> {
     let omnilock_config = OmniLockConfig::new_pubkey_hash(args.pubkey_hash.clone());
-    build_transfer_tx_(&args.common_args, env, omnilock_config)
+    build_transfer_tx_(&args.common_args, env, omnilock_config, Default::default())
+}
+
+/// The omnilock cell and the node backed resolvers, collector and providers the transactions
+/// are built with.
+struct TxBuildContext {
+    ckb_client: CkbRpcClient,
+    genesis_block: BlockView,
+    omnilock: OmniLockInfo,
+    cell_dep_resolver: DefaultCellDepResolver,
+    header_dep_resolver: DefaultHeaderDepResolver,
+    cell_collector: DefaultCellCollector,
+    tx_dep_provider: DefaultTransactionDependencyProvider,
+}
+
+impl TxBuildContext {
+    fn new(env: &ConfigContext) -> Result<TxBuildContext> {
+        let omnilock = env.omnilock_info()?;
+        let mut ckb_client = CkbRpcClient::new(env.ckb_rpc.as_str());
+        let genesis_block = ckb_client.get_block_by_number(0.into())?.unwrap();
+        let genesis_block = BlockView::from(genesis_block);
+        let mut cell_dep_resolver = DefaultCellDepResolver::from_genesis(&genesis_block)?;
+        cell_dep_resolver.insert(
+            omnilock.script_id.clone(),
+            omnilock.cell_dep.clone(),
+            "Omni Lock".to_string(),
+        );
+        Ok(TxBuildContext {
+            ckb_client,
+            genesis_block,
+            omnilock,
+            cell_dep_resolver,
+            header_dep_resolver: DefaultHeaderDepResolver::new(env.ckb_rpc.as_str()),
+            cell_collector: DefaultCellCollector::new(env.ckb_rpc.as_str()),
+            tx_dep_provider: DefaultTransactionDependencyProvider::new(env.ckb_rpc.as_str(), 10),
+        })
+    }
+
+    /// The omnilock lock script of the config, with the configured code hash and hash type.
+    fn omnilock_script(&self, config: &OmniLockConfig) -> Script {
+        export::omnilock_script(&self.omnilock, config)
+    }
+
+    fn is_omnilock(&self, lock: &Script) -> bool {
+        ScriptId::from(lock) == self.omnilock.script_id
+    }
+
+    fn secp256k1_data_dep(&self) -> CellDep {
+        // pub const SECP256K1_DATA_OUTPUT_LOC: (usize, usize) = (0, 3);
+        build_cell_dep(&self.genesis_block.transactions()[0].hash().unpack(), 3)
+    }
+
+    /// Fill the placeholder witnesses of the unlockers, then balance the capacity.
+    fn complete_tx(
+        &mut self,
+        base_tx: TransactionView,
+        unlockers: &HashMap<ScriptId, Box<dyn ScriptUnlocker>>,
+        balancer: &CapacityBalancer,
+    ) -> Result<TransactionView> {
+        let (tx_filled_witnesses, _) =
+            fill_placeholder_witnesses(base_tx, &self.tx_dep_provider, unlockers)
+                .with_context(|| "try to fill placeholder witnesses".to_string())?;
+        self.balance_tx(&tx_filled_witnesses, balancer)
+    }
+
+    fn balance_tx(
+        &mut self,
+        tx: &TransactionView,
+        balancer: &CapacityBalancer,
+    ) -> Result<TransactionView> {
+        balance_tx_capacity(
+            tx,
+            balancer,
+            &mut self.cell_collector,
+            &self.tx_dep_provider,
+            &self.cell_dep_resolver,
+            &self.header_dep_resolver,
+        )
+        .with_context(|| "try to balance capacity".to_string())
+    }
+
+    /// Refuse the transaction if any input of the config's time-locked omnilock can't be spent yet.
+    fn check_time_lock(&mut self, tx: &TransactionView, config: &OmniLockConfig) -> Result<()> {
+        let since = match time_lock_since(config) {
+            Some(since) => since,
+            None => return Ok(()),
+        };
+        let lock = self.omnilock_script(config);
+        for input in tx.inputs() {
+            let out_point = input.previous_output();
+            if self.tx_dep_provider.get_cell(&out_point)?.lock() == lock {
+                check_since_satisfied(&mut self.ckb_client, &out_point, since)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 fn build_transfer_tx_(
     args: &CommonArgs,
     env: &ConfigContext,
-    omnilock_config: OmniLockConfig,
-) -> Result<(TransactionView, OmniLockConfig, PathBuf)> {
-    let mut ckb_client = CkbRpcClient::new(env.ckb_rpc.as_str());
-    let cell = build_omnilock_cell_dep_from_client(
-        &mut ckb_client,
-        &env.omnilock_tx_hash,
-        env.omnilock_index,
-    )?;
-    // Build CapacityBalancer
-    let sender = Script::new_builder()
-        .code_hash(cell.type_hash.pack())
-        .hash_type(ScriptHashType::Type.into())
-        .args(omnilock_config.build_args().pack())
-        .build();
-    let placeholder_witness = omnilock_config.placeholder_witness(OmniUnlockMode::Normal)?;
-    let balancer = CapacityBalancer::new_simple(sender, placeholder_witness, 1000);
-
-    // Build:
-    //   * CellDepResolver
-    //   * HeaderDepResolver
-    //   * CellCollector
-    //   * TransactionDependencyProvider
-    // let mut ckb_client = CkbRpcClient::new(args.ckb_rpc.as_str());
-    let genesis_block = ckb_client.get_block_by_number(0.into())?.unwrap();
-    let genesis_block = BlockView::from(genesis_block);
-    let mut cell_dep_resolver = DefaultCellDepResolver::from_genesis(&genesis_block)?;
-    cell_dep_resolver.insert(cell.script_id, cell.cell_dep, "Omni Lock".to_string());
-    let header_dep_resolver = DefaultHeaderDepResolver::new(env.ckb_rpc.as_str());
-    let mut cell_collector = DefaultCellCollector::new(env.ckb_rpc.as_str());
-    let tx_dep_provider = DefaultTransactionDependencyProvider::new(env.ckb_rpc.as_str(), 10);
+    mut omnilock_config: OmniLockConfig,
+    mut extra: UnlockExtra,
+) -> Result<(TxInfo, PathBuf)> {
+    args.flag_args.apply(&mut omnilock_config)?;
+    let mut ctx = TxBuildContext::new(env)?;
+    let rc_cell_deps = args
+        .rc_cell_args
+        .cell_deps(&mut ctx.ckb_client, &omnilock_config)?;
+    extra.cell_deps.extend(rc_cell_deps);
+    let sender = ctx.omnilock_script(&omnilock_config);
+    let placeholder_witness =
+        placeholder_witness(&omnilock_config, extra.preimage.as_ref(), extra.algorithm)?;
+    let balancer = CapacityBalancer::new_simple_with_since(
+        sender,
+        placeholder_witness,
+        omnilock_config.get_since_source(),
+        1000,
+    );
 
     // Build base transaction
-    let unlockers = build_omnilock_unlockers(Vec::new(), omnilock_config.clone(), cell.type_hash);
+    let unlockers = build_omnilock_unlockers(
+        SignerKeys::default(),
+        omnilock_config.clone(),
+        extra.preimage.clone(),
+        extra.algorithm,
+        ctx.omnilock.script_id.clone(),
+    );
     let output = CellOutput::new_builder()
         .lock(Script::from(&args.receiver))
         .capacity(args.capacity.0.pack())
@@ -162,76 +540,648 @@ fn build_transfer_tx_(
     let builder = CapacityTransferBuilder::new(vec![(output, Bytes::default())]);
 
     let base_tx = builder.build_base(
-        &mut cell_collector,
-        &cell_dep_resolver,
-        &header_dep_resolver,
-        &tx_dep_provider,
+        &mut ctx.cell_collector,
+        &ctx.cell_dep_resolver,
+        &ctx.header_dep_resolver,
+        &ctx.tx_dep_provider,
     )?;
+    let mut base_tx = base_tx
+        .as_advanced_builder()
+        .cell_dep(ctx.secp256k1_data_dep())
+        .cell_deps(extra.cell_deps)
+        .build();
+    if let Some(owner) = extra.owner.as_ref() {
+        base_tx = add_owner_input(
+            base_tx,
+            owner,
+            &mut ctx.cell_collector,
+            &ctx.cell_dep_resolver,
+        )?;
+    }
+    let tx = ctx.complete_tx(base_tx, &unlockers, &balancer)?;
+    ctx.check_time_lock(&tx, &omnilock_config)?;
+    let mut tx_info = TxInfo::new(tx, omnilock_config);
+    tx_info.owner_multisig_config = extra.owner.and_then(|owner| owner.multisig_config);
+    tx_info.preimage = extra.preimage.map(JsonBytes::from_bytes);
+    if extra.algorithm != DelegateAlgorithm::Secp256k1 {
+        tx_info.algorithm = Some(extra.algorithm);
+    }
+    Ok((tx_info, args.tx_file.clone()))
+}
 
-    let secp256k1_data_dep = {
-        // pub const SECP256K1_DATA_OUTPUT_LOC: (usize, usize) = (0, 3);
-        let tx_hash = genesis_block.transactions()[0].hash();
-        let out_point = OutPoint::new(tx_hash, 3u32);
-        CellDep::new_builder().out_point(out_point).build()
+/// The RC cell of the administrator mode, checked against the type ID in the args.
+fn build_rc_cell_dep(
+    ckb_client: &mut CkbRpcClient,
+    args: &RcCellArgs,
+    rc_type_id: &H256,
+) -> Result<CellDep> {
+    let (tx_hash, index) = match (&args.rc_cell_tx_hash, args.rc_cell_index) {
+        (Some(tx_hash), Some(index)) => (tx_hash, index),
+        _ => bail!("--rc-cell-tx-hash and --rc-cell-index are required by the administrator mode"),
     };
+    let type_id = get_code_hash_from_client(ckb_client, tx_hash, index, ScriptHashType::Type)?;
+    ensure!(
+        &type_id == rc_type_id,
+        "The type ID of the RC cell is {:#x}, not the given one {:#x}",
+        type_id,
+        rc_type_id
+    );
+    let out_point = OutPoint::new(tx_hash.pack(), index);
+    Ok(CellDep::new_builder().out_point(out_point).build())
+}
 
-    let base_tx = base_tx
+/// Put a live cell of the owner into the inputs, and send the same cell back to the owner.
+fn add_owner_input(
+    base_tx: TransactionView,
+    owner: &OwnerInput,
+    cell_collector: &mut dyn CellCollector,
+    cell_dep_resolver: &dyn CellDepResolver,
+) -> Result<TransactionView> {
+    let mut query = CellQueryOptions::new_lock(owner.lock.clone());
+    query.secondary_script_len_range = Some(ValueRangeOption::new_exact(0));
+    query.data_len_range = Some(ValueRangeOption::new_exact(0));
+    let (cells, _) = cell_collector.collect_live_cells(&query, true)?;
+    let cell = cells
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("can not find a live cell of the owner lock"))?;
+    let owner_dep = cell_dep_resolver
+        .resolve(&owner.lock)
+        .ok_or_else(|| anyhow!("can not resolve the cell dep of the owner lock"))?;
+
+    let input_index = base_tx.inputs().len();
+    let mut witnesses: Vec<packed::Bytes> = base_tx.witnesses().into_iter().collect();
+    witnesses.resize(input_index, Default::default());
+    witnesses.push(owner.placeholder_witness.as_bytes().pack());
+    Ok(base_tx
         .as_advanced_builder()
-        .cell_dep(secp256k1_data_dep)
-        .build();
-    let (tx_filled_witnesses, _) =
-        fill_placeholder_witnesses(base_tx, &tx_dep_provider, &unlockers)
-            .with_context(|| "try to fill placeholder witnesses".to_string())?;
-
-    let tx = balance_tx_capacity(
-        &tx_filled_witnesses,
-        &balancer,
-        &mut cell_collector,
-        &tx_dep_provider,
-        &cell_dep_resolver,
-        &header_dep_resolver,
-    )
-    .with_context(|| "try to balance capacity".to_string())?;
-    Ok((tx, omnilock_config, args.tx_file.clone()))
+        .input(CellInput::new(cell.out_point, 0))
+        .output(cell.output)
+        .output_data(cell.output_data.pack())
+        .cell_dep(owner_dep)
+        .set_witnesses(witnesses)
+        .build())
 }
 
 fn build_ethereum_transfer_tx(
     args: &GenerateEthereumArgs,
     env: &ConfigContext,
-) -> Result<(TransactionView, OmniLockConfig, PathBuf)> {
+) -> Result<(TxInfo, PathBuf)> {
     let omnilock_config = OmniLockConfig::new_ethereum(args.sender_address.clone());
-    build_transfer_tx_(&args.common_args, env, omnilock_config)
+    build_transfer_tx_(&args.common_args, env, omnilock_config, Default::default())
+}
+
+fn build_eos_transfer_tx(args: &GenerateEosArgs, env: &ConfigContext) -> Result<(TxInfo, PathBuf)> {
+    let pubkey_hash = blake160(&args.sender_pubkey.serialize());
+    let omnilock_config = new_omnilock_config(IdentityFlag::Eos, pubkey_hash)?;
+    build_transfer_tx_(&args.common_args, env, omnilock_config, Default::default())
+}
+
+fn build_tron_transfer_tx(
+    args: &GenerateTronArgs,
+    env: &ConfigContext,
+) -> Result<(TxInfo, PathBuf)> {
+    let omnilock_config = new_omnilock_config(IdentityFlag::Tron, args.sender_address.clone())?;
+    build_transfer_tx_(&args.common_args, env, omnilock_config, Default::default())
+}
+
+fn build_bitcoin_transfer_tx(
+    args: &GenerateBitcoinArgs,
+    env: &ConfigContext,
+) -> Result<(TxInfo, PathBuf)> {
+    let omnilock_config = new_omnilock_config(IdentityFlag::Bitcoin, args.sender_address.clone())?;
+    build_transfer_tx_(&args.common_args, env, omnilock_config, Default::default())
+}
+
+fn build_dogecoin_transfer_tx(
+    args: &GenerateDogecoinArgs,
+    env: &ConfigContext,
+) -> Result<(TxInfo, PathBuf)> {
+    let omnilock_config = new_omnilock_config(IdentityFlag::Dogecoin, args.sender_address.clone())?;
+    build_transfer_tx_(&args.common_args, env, omnilock_config, Default::default())
 }
 
 fn build_multisig_transfer_tx(
     args: &GenerateMultiSigArgs,
     env: &ConfigContext,
-) -> Result<(TransactionView, OmniLockConfig, PathBuf)> {
-    let multisig_config =
-        build_multisig_config(&args.sighash_address, args.require_first_n, args.threshold)?;
+) -> Result<(TxInfo, PathBuf)> {
+    let multisig_config = build_multisig_config(
+        &args.member_args.members,
+        args.require_first_n,
+        args.threshold,
+    )?;
 
     let omnilock_config = OmniLockConfig::new_multisig(multisig_config);
-    build_transfer_tx_(&args.common_args, env, omnilock_config)
+    build_transfer_tx_(&args.common_args, env, omnilock_config, Default::default())
+}
+
+fn build_ownerlock_transfer_tx(
+    args: &GenerateOwnerLockArgs,
+    env: &ConfigContext,
+) -> Result<(TxInfo, PathBuf)> {
+    let lock = Script::from(&args.owner_address);
+    let code_hash: H256 = lock.code_hash().unpack();
+    let lock_args = lock.args().raw_data();
+    ensure!(
+        lock.hash_type() == ScriptHashType::Type.into() && lock_args.len() == 20,
+        "The owner address must be a sighash or multisig address!"
+    );
+    let owner = if code_hash == SIGHASH_TYPE_HASH {
+        let placeholder_witness = WitnessArgs::new_builder()
+            .lock(Some(Bytes::from(vec![0u8; 65])).pack())
+            .build();
+        OwnerInput {
+            lock,
+            placeholder_witness,
+            multisig_config: None,
+        }
+    } else if code_hash == MULTISIG_TYPE_HASH {
+        let (require_first_n, threshold) = match (args.require_first_n, args.threshold) {
+            (Some(require_first_n), Some(threshold)) => (require_first_n, threshold),
+            _ => {
+                bail!("--require-first-n and --threshold are required by a multisig owner address")
+            }
+        };
+        let multisig_config =
+            build_multisig_config(&args.member_args.members, require_first_n, threshold)?;
+        ensure!(
+            multisig_config.hash160().as_bytes() == lock_args.as_ref(),
+            "The multisig config does not match the owner address!"
+        );
+        OwnerInput {
+            lock,
+            placeholder_witness: multisig_config.placeholder_witness(),
+            multisig_config: Some(multisig_config),
+        }
+    } else {
+        bail!("The owner address must be a sighash or multisig address!");
+    };
+
+    let owner_lock_hash = owner.lock.calc_script_hash();
+    let omnilock_config =
+        OmniLockConfig::new_ownerlock(H160::from_slice(&owner_lock_hash.as_slice()[0..20])?);
+    let extra = UnlockExtra {
+        owner: Some(owner),
+        ..Default::default()
+    };
+    build_transfer_tx_(&args.common_args, env, omnilock_config, extra)
+}
+
+fn build_exec_transfer_tx(
+    args: &GenerateExecArgs,
+    env: &ConfigContext,
+) -> Result<(TxInfo, PathBuf)> {
+    let preimage = args.exec_args.preimage();
+    let omnilock_config = new_omnilock_config(IdentityFlag::Exec, blake160(&preimage))?;
+    let extra = UnlockExtra {
+        preimage: Some(preimage),
+        algorithm: args.exec_args.algorithm(),
+        cell_deps: args.cell_dep_args.cell_deps(),
+        ..Default::default()
+    };
+    build_transfer_tx_(&args.common_args, env, omnilock_config, extra)
+}
+
+fn build_dl_transfer_tx(args: &GenerateDlArgs, env: &ConfigContext) -> Result<(TxInfo, PathBuf)> {
+    let library_dep = build_library_cell_dep(
+        env,
+        &args.library_tx_hash,
+        args.library_index,
+        args.dl_args.code_hash(),
+        args.dl_args.hash_type(),
+    )?;
+    let preimage = args.dl_args.preimage();
+    let omnilock_config = new_omnilock_config(IdentityFlag::Dl, blake160(&preimage))?;
+    let extra = UnlockExtra {
+        preimage: Some(preimage),
+        algorithm: args.dl_args.algorithm(),
+        cell_deps: vec![library_dep],
+        ..Default::default()
+    };
+    build_transfer_tx_(&args.common_args, env, omnilock_config, extra)
+}
+
+/// The dynamic library is loaded from the cell deps, check it's the one in the preimage.
+fn build_library_cell_dep(
+    env: &ConfigContext,
+    tx_hash: &H256,
+    index: u32,
+    code_hash: &H256,
+    hash_type: ScriptHashType,
+) -> Result<CellDep> {
+    let mut ckb_client = CkbRpcClient::new(env.ckb_rpc.as_str());
+    let library_code_hash = get_code_hash_from_client(&mut ckb_client, tx_hash, index, hash_type)?;
+    ensure!(
+        &library_code_hash == code_hash,
+        "The code hash of the library cell is {:#x}, not the given one {:#x}",
+        library_code_hash,
+        code_hash
+    );
+    let out_point = OutPoint::new(tx_hash.pack(), index);
+    Ok(CellDep::new_builder().out_point(out_point).build())
+}
+
+fn build_identity_transfer_tx(
+    args: &GenerateIdentityArgs,
+    env: &ConfigContext,
+) -> Result<(TxInfo, PathBuf)> {
+    let identity = IdentityFile::load(&args.identity)?;
+    ensure!(
+        !args.common_args.flag_args.is_set(),
+        "The flags are given by the identity file, they can't be given again"
+    );
+    let omnilock_config = identity.omnilock_config()?;
+    let preimage = identity.preimage();
+    let cell_deps = match (omnilock_config.id().flag(), preimage.as_ref()) {
+        (IdentityFlag::OwnerLock, _) => {
+            bail!("The owner lock needs a live cell of the owner, use `generate-tx owner-lock`")
+        }
+        (IdentityFlag::Dl, Some(preimage)) => {
+            let cell_dep = &args.cell_dep_args;
+            let (tx_hash, index) = cell_dep
+                .cell_dep_tx_hash
+                .as_ref()
+                .zip(cell_dep.cell_dep_index)
+                .ok_or_else(|| anyhow!("The cell dep of the dynamic library is required"))?;
+            ensure!(
+                preimage.len() == 53,
+                "Invalid preimage length of the dl auth: {}",
+                preimage.len()
+            );
+            let hash_type = ScriptHashType::try_from(preimage[32])
+                .map_err(|err| anyhow!("Invalid hash type in the preimage: {}", err))?;
+            let code_hash = H256::from_slice(&preimage[0..32])?;
+            let library_dep = build_library_cell_dep(env, tx_hash, index, &code_hash, hash_type)?;
+            vec![library_dep]
+        }
+        _ => args.cell_dep_args.cell_deps(),
+    };
+    let extra = UnlockExtra {
+        preimage,
+        algorithm: identity.algorithm(),
+        cell_deps,
+        ..Default::default()
+    };
+    build_transfer_tx_(&args.common_args, env, omnilock_config, extra)
+}
+
+fn build_acp_deposit_tx(
+    args: &GenerateAcpDepositArgs,
+    env: &ConfigContext,
+) -> Result<(TxInfo, PathBuf)> {
+    let mut ctx = TxBuildContext::new(env)?;
+    let acp_lock = Script::from(&args.acp_address);
+    ensure!(
+        ctx.is_omnilock(&acp_lock),
+        "{} is not an omnilock address",
+        args.acp_address
+    );
+    let omnilock_config = omnilock_config_from_args(acp_lock.args().raw_data().as_ref())?;
+    let acp = acp_config(&omnilock_config)
+        .ok_or_else(|| anyhow!("{} is not in anyone-can-pay mode", args.acp_address))?;
+    let min_ckb = 10u64.pow(acp.ckb_minimum as u32);
+    ensure!(
+        args.capacity.0 >= min_ckb,
+        "the deposit is less than the minimum {} CKB of the anyone-can-pay cell",
+        HumanCapacity(min_ckb)
+    );
+
+    let sender = Script::from(&args.sender_address);
+    ensure!(
+        sender.code_hash().as_slice() == SIGHASH_TYPE_HASH.as_bytes()
+            && sender.hash_type() == ScriptHashType::Type.into(),
+        "The sender address must be a sighash address!"
+    );
+    let placeholder_witness = WitnessArgs::new_builder()
+        .lock(Some(Bytes::from(vec![0u8; 65])).pack())
+        .build();
+    let balancer = CapacityBalancer::new_simple(sender, placeholder_witness, 1000);
+
+    // Put the anyone-can-pay cell into the inputs, and send it back with the deposit
+    let mut query = CellQueryOptions::new_lock(acp_lock);
+    query.secondary_script_len_range = Some(ValueRangeOption::new_exact(0));
+    query.data_len_range = Some(ValueRangeOption::new_exact(0));
+    let (cells, _) = ctx.cell_collector.collect_live_cells(&query, true)?;
+    let acp_cell = cells
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("can not find a live cell of {}", args.acp_address))?;
+    let capacity: u64 = acp_cell.output.capacity().unpack();
+    let output = acp_cell
+        .output
+        .as_builder()
+        .capacity((capacity + args.capacity.0).pack())
+        .build();
+    // The time lock is checked for every input of the omnilock, deposits included
+    let since = time_lock_since(&omnilock_config).unwrap_or_default();
+    if since != 0 {
+        check_since_satisfied(&mut ctx.ckb_client, &acp_cell.out_point, since)?;
+    }
+    let base_tx = TransactionView::new_advanced_builder()
+        .input(CellInput::new(acp_cell.out_point, since))
+        .output(output)
+        .output_data(acp_cell.output_data.pack())
+        .cell_dep(ctx.omnilock.cell_dep.clone())
+        .witness(packed::Bytes::default())
+        .build();
+
+    let tx = ctx.balance_tx(&base_tx, &balancer)?;
+    Ok((TxInfo::new(tx, omnilock_config), args.tx_file.clone()))
+}
+
+fn build_issue_xudt_tx(
+    args: &GenerateIssueXudtArgs,
+    env: &ConfigContext,
+) -> Result<(TxInfo, PathBuf)> {
+    if args.max_supply.is_some() {
+        // The type ID args are the hash of the transaction's first input, so the hash can't be
+        // known when the issuer's address is built
+        ensure!(
+            ScriptId::from(&args.info_cell_type) != ScriptId::new_type(TYPE_ID_CODE_HASH),
+            "the type hash of a type ID info cell depends on the transaction creating it, it can't match the --supply-info-type-hash of {}, use a type script whose hash is known in advance",
+            args.issuer_address
+        );
+    }
+    let mut ctx = TxBuildContext::new(env)?;
+    let issuer = Script::from(&args.issuer_address);
+    ensure!(
+        ctx.is_omnilock(&issuer),
+        "{} is not an omnilock address",
+        args.issuer_address
+    );
+    let supply_config = omnilock_config_from_args(issuer.args().raw_data().as_ref())?;
+    let info_type_hash: H256 = args.info_cell_type.calc_script_hash().unpack();
+    ensure!(
+        supply_config.get_info_cell() == Some(&info_type_hash),
+        "{} is not in supply mode with the info cell type hash {:#x}",
+        args.issuer_address,
+        info_type_hash
+    );
+    // The signature is all it takes to unlock the issuer's cells
+    ensure!(
+        !matches!(
+            supply_config.id().flag(),
+            IdentityFlag::Multisig
+                | IdentityFlag::OwnerLock
+                | IdentityFlag::Exec
+                | IdentityFlag::Dl
+        ),
+        "issuing xUDT by a {:?} omnilock is not supported",
+        supply_config.id().flag()
+    );
+
+    let xudt_code_hash = get_code_hash_from_client(
+        &mut ctx.ckb_client,
+        &args.xudt_tx_hash,
+        args.xudt_index,
+        args.xudt_hash_type,
+    )?;
+    let xudt_type = Script::new_builder()
+        .code_hash(xudt_code_hash.pack())
+        .hash_type(args.xudt_hash_type.into())
+        .args(issuer.calc_script_hash().as_bytes().pack())
+        .build();
+    let xudt_type_hash: H256 = xudt_type.calc_script_hash().unpack();
+
+    let query = CellQueryOptions::new_type(args.info_cell_type.clone());
+    let (info_cells, _) = ctx.cell_collector.collect_live_cells(&query, true)?;
+    let info_cell = info_cells.into_iter().next();
+
+    let mut base_tx = TransactionView::new_advanced_builder()
+        .cell_dep(ctx.omnilock.cell_dep.clone())
+        .cell_dep(ctx.secp256k1_data_dep())
+        .build();
+    if let (Some(tx_hash), Some(index)) = (&args.info_type_dep_tx_hash, args.info_type_dep_index) {
+        base_tx = base_tx
+            .as_advanced_builder()
+            .cell_dep(build_cell_dep(tx_hash, index))
+            .build();
+    }
+    // The supply mode requires the info cell in the inputs, so it's created by the issuer's
+    // omnilock without any flag
+    let omnilock_config = if let Some(max_supply) = args.max_supply {
+        ensure!(
+            info_cell.is_none(),
+            "the info cell of type hash {:#x} already exists",
+            info_type_hash
+        );
+        let data = InfoCellData::new_simple(0, max_supply, xudt_type_hash).pack();
+        let output = CellOutput::new_builder()
+            .lock(issuer)
+            .type_(Some(args.info_cell_type.clone()).pack())
+            .build();
+        let output = with_occupied_capacity(output, &data)?;
+        base_tx = base_tx
+            .as_advanced_builder()
+            .output(output)
+            .output_data(data.pack())
+            .build();
+        new_omnilock_config(
+            supply_config.id().flag(),
+            supply_config.id().auth_content().clone(),
+        )?
+    } else {
+        let info_cell = info_cell.ok_or_else(|| {
+            anyhow!(
+                "can not find the info cell of type hash {:#x}, create it with --max-supply first",
+                info_type_hash
+            )
+        })?;
+        ensure!(
+            info_cell.output.lock() == issuer,
+            "the info cell is not locked by {}",
+            args.issuer_address
+        );
+        let mut data = parse_info_cell_data(&info_cell.output_data)?;
+        ensure!(
+            data.sudt_script_hash == xudt_type_hash,
+            "the info cell records the supply of xUDT {:#x}, not {:#x}",
+            data.sudt_script_hash,
+            xudt_type_hash
+        );
+        let amount = args
+            .amount
+            .filter(|amount| *amount > 0)
+            .ok_or_else(|| anyhow!("the amount of xUDT to mint must be greater than 0"))?;
+        data.current_supply = data
+            .current_supply
+            .checked_add(amount)
+            .filter(|supply| *supply <= data.max_supply)
+            .ok_or_else(|| {
+                anyhow!(
+                    "minting {} exceeds the max supply {}, the current supply is {}",
+                    amount,
+                    data.max_supply,
+                    data.current_supply
+                )
+            })?;
+        let since = time_lock_since(&supply_config).unwrap_or_default();
+        let rc_cell_deps = args
+            .rc_cell_args
+            .cell_deps(&mut ctx.ckb_client, &supply_config)?;
+        let xudt_output = CellOutput::new_builder()
+            .lock(Script::from(args.receiver.as_ref().unwrap()))
+            .type_(Some(xudt_type).pack())
+            .build();
+        let xudt_data = Bytes::from(amount.to_le_bytes().to_vec());
+        let xudt_output = with_occupied_capacity(xudt_output, &xudt_data)?;
+        base_tx = base_tx
+            .as_advanced_builder()
+            .input(CellInput::new(info_cell.out_point, since))
+            .output(info_cell.output)
+            .output_data(data.pack().pack())
+            .output(xudt_output)
+            .output_data(xudt_data.pack())
+            .cell_dep(build_cell_dep(&args.xudt_tx_hash, args.xudt_index))
+            .cell_deps(rc_cell_deps)
+            .witness(packed::Bytes::default())
+            .build();
+        supply_config
+    };
+
+    let sender = ctx.omnilock_script(&omnilock_config);
+    let placeholder_witness =
+        placeholder_witness(&omnilock_config, None, DelegateAlgorithm::Secp256k1)?;
+    let balancer = CapacityBalancer::new_simple_with_since(
+        sender,
+        placeholder_witness,
+        omnilock_config.get_since_source(),
+        1000,
+    );
+    let unlockers = build_omnilock_unlockers(
+        SignerKeys::default(),
+        omnilock_config.clone(),
+        None,
+        DelegateAlgorithm::Secp256k1,
+        ctx.omnilock.script_id.clone(),
+    );
+    let tx = ctx.complete_tx(base_tx, &unlockers, &balancer)?;
+    ctx.check_time_lock(&tx, &omnilock_config)?;
+    Ok((TxInfo::new(tx, omnilock_config), args.tx_file.clone()))
+}
+
+fn build_cell_dep(tx_hash: &H256, index: u32) -> CellDep {
+    let out_point = OutPoint::new(tx_hash.pack(), index);
+    CellDep::new_builder().out_point(out_point).build()
+}
+
+/// Set the capacity of the output to the minimum it occupies with the data.
+fn with_occupied_capacity(output: CellOutput, data: &Bytes) -> Result<CellOutput> {
+    let capacity = output
+        .occupied_capacity(Capacity::bytes(data.len())?)
+        .map_err(|err| anyhow!("{}", err))?;
+    Ok(output.as_builder().capacity(capacity.pack()).build())
+}
+
+/// Parse the data of the info cell, the reverse of `InfoCellData::pack`.
+fn parse_info_cell_data(data: &[u8]) -> Result<InfoCellData> {
+    ensure!(
+        data.len() >= 65,
+        "invalid info cell data length: {}, expected not less than 65",
+        data.len()
+    );
+    let mut current_supply = [0u8; 16];
+    current_supply.copy_from_slice(&data[1..17]);
+    let mut max_supply = [0u8; 16];
+    max_supply.copy_from_slice(&data[17..33]);
+    let mut info = InfoCellData::new(
+        u128::from_le_bytes(current_supply),
+        u128::from_le_bytes(max_supply),
+        H256::from_slice(&data[33..65])?,
+        data[65..].to_vec(),
+    );
+    info.version = data[0];
+    Ok(info)
+}
+
+/// The keys the omnilock signer signs with, none by default.
+#[derive(Clone, Default)]
+pub struct SignerKeys {
+    /// The keys of the auth types based on secp256k1, and of the schnorr verifier
+    pub secp256k1: Vec<PrivkeyWrapper>,
+    /// The keys of the ed25519 verifier of the exec and dl auth
+    pub ed25519: Vec<ed25519_dalek::SigningKey>,
+}
+
+impl SignerKeys {
+    pub fn secp256k1(keys: Vec<PrivkeyWrapper>) -> SignerKeys {
+        SignerKeys {
+            secp256k1: keys,
+            ed25519: Vec::new(),
+        }
+    }
+
+    pub fn ed25519(keys: Vec<ed25519_dalek::SigningKey>) -> SignerKeys {
+        SignerKeys {
+            secp256k1: Vec::new(),
+            ed25519: keys,
+        }
+    }
+}
+
+/// Build the signer of the omnilock config's auth type with the given keys.
+pub fn build_omnilock_signer(
+    signer_keys: SignerKeys,
+    config: &OmniLockConfig,
+    algorithm: DelegateAlgorithm,
+) -> CommonSigner {
+    let keys: Vec<secp256k1::SecretKey> = signer_keys.secp256k1.iter().map(|k| k.0).collect();
+    let signer: Box<dyn Signer> = match config.id().flag() {
+        IdentityFlag::Ethereum => {
+            Box::new(SecpCkbRawKeySigner::new_with_ethereum_secret_keys(keys))
+        }
+        IdentityFlag::Eos => Box::new(EosSigner::new(keys)),
+        IdentityFlag::Tron => Box::new(TronSigner::new(keys)),
+        IdentityFlag::Bitcoin => Box::new(BtcMessageSigner::new_bitcoin(keys)),
+        IdentityFlag::Dogecoin => Box::new(BtcMessageSigner::new_dogecoin(keys)),
+        IdentityFlag::Exec | IdentityFlag::Dl if algorithm == DelegateAlgorithm::Schnorr => {
+            Box::new(SchnorrSigner::new(keys))
+        }
+        _ => Box::new(SecpCkbRawKeySigner::new_with_secret_keys(keys)),
+    };
+    let mut signers = vec![signer];
+    if !signer_keys.ed25519.is_empty() {
+        signers.push(Box::new(Ed25519Signer::new(signer_keys.ed25519)));
+    }
+    CommonSigner::new(signers)
 }
 
 pub fn build_omnilock_unlockers(
-    keys: Vec<PrivkeyWrapper>,
+    keys: SignerKeys,
     config: OmniLockConfig,
-    omni_lock_type_hash: H256,
+    preimage: Option<Bytes>,
+    algorithm: DelegateAlgorithm,
+    omnilock_script_id: ScriptId,
 ) -> HashMap<ScriptId, Box<dyn ScriptUnlocker>> {
-    let keys: Vec<secp256k1::SecretKey> = keys.iter().map(|k| k.0).collect();
-    let signer = if config.is_ethereum() {
-        SecpCkbRawKeySigner::new_with_ethereum_secret_keys(keys)
+    let omnilock_signer = Box::new(build_omnilock_signer(keys, &config, algorithm));
+    build_omnilock_unlockers_with_signer(
+        omnilock_signer,
+        config,
+        preimage,
+        algorithm,
+        omnilock_script_id,
+    )
+}
+
+/// Build the omnilock unlockers with the signer of a key that is not secp256k1.
+pub fn build_omnilock_unlockers_with_signer(
+    omnilock_signer: Box<dyn Signer>,
+    config: OmniLockConfig,
+    preimage: Option<Bytes>,
+    algorithm: DelegateAlgorithm,
+    omnilock_script_id: ScriptId,
+) -> HashMap<ScriptId, Box<dyn ScriptUnlocker>> {
+    let omnilock_unlocker: Box<dyn ScriptUnlocker> = if is_sdk_supported(&config) {
+        let omnilock_signer =
+            OmniLockScriptSigner::new(omnilock_signer, config.clone(), OmniUnlockMode::Normal);
+        Box::new(OmniLockUnlocker::new(omnilock_signer, config))
     } else {
-        SecpCkbRawKeySigner::new_with_secret_keys(keys)
+        Box::new(OmniLockSigUnlocker::new(
+            omnilock_signer,
+            config,
+            preimage,
+            algorithm,
+        ))
     };
-    let omnilock_signer = Box::new(CommonSigner::new(vec![Box::new(signer)]));
-    let omnilock_signer =
-        OmniLockScriptSigner::new(omnilock_signer, config.clone(), OmniUnlockMode::Normal);
-    let omnilock_unlocker = OmniLockUnlocker::new(omnilock_signer, config);
-    let omnilock_script_id = ScriptId::new_type(omni_lock_type_hash);
-    HashMap::from([(
-        omnilock_script_id,
-        Box::new(omnilock_unlocker) as Box<dyn ScriptUnlocker>,
-    )])
+    HashMap::from([(omnilock_script_id, omnilock_unlocker)])
 }
@@ -0,0 +1,141 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    slice,
+    str::FromStr,
+};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use bip39::Mnemonic;
+use bitcoin::{
+    network::constants::Network,
+    util::bip32::{DerivationPath, ExtendedPrivKey},
+};
+use ckb_sdk::{
+    util::{zeroize_privkey, zeroize_slice},
+    SECP256K1,
+};
+use clap::Args;
+use rpassword::prompt_password_stdout;
+use zeroize::Zeroizing;
+
+use crate::arg_parser::PrivkeyWrapper;
+
+/// The BIP44 derivation path of CKB keys, `i` is the address index.
+pub(crate) const CKB_PATH_TEMPLATE: &str = "m/44'/309'/0'/0/i";
+/// The BIP44 derivation path of Ethereum keys, `i` is the address index.
+pub(crate) const ETHEREUM_PATH_TEMPLATE: &str = "m/44'/60'/0'/0/i";
+
+/// A BIP32 derivation path, one of its levels may be `i` or `i'`, which is replaced by the index.
+pub(crate) struct PathTemplate(String);
+
+impl PathTemplate {
+    pub(crate) fn new(template: &str) -> Result<Self> {
+        let template = PathTemplate(template.to_owned());
+        template.path(0)?;
+        Ok(template)
+    }
+
+    pub(crate) fn has_index(&self) -> bool {
+        self.0.split('/').any(|level| level == "i" || level == "i'")
+    }
+
+    /// The derivation path of the index.
+    pub(crate) fn path(&self, index: u32) -> Result<DerivationPath> {
+        let levels: Vec<String> = self
+            .0
+            .split('/')
+            .map(|level| match level {
+                "i" => index.to_string(),
+                "i'" => format!("{}'", index),
+                _ => level.to_owned(),
+            })
+            .collect();
+        let path = levels.join("/");
+        DerivationPath::from_str(&path)
+            .map_err(|err| anyhow!("invalid derivation path {}: {}", path, err))
+    }
+}
+
+/// The BIP39 seed of the mnemonic in the file, the passphrase is prompted if asked. The seed,
+/// the words read from the file and the passphrase are zeroized when dropped.
+pub(crate) fn load_seed(
+    mnemonic_file: &Path,
+    with_passphrase: bool,
+) -> Result<Zeroizing<[u8; 64]>> {
+    let content = Zeroizing::new(
+        fs::read(mnemonic_file)
+            .with_context(|| format!("try to read file {}", mnemonic_file.display()))?,
+    );
+    let mnemonic = std::str::from_utf8(&content)
+        .map_err(|err| anyhow!(err))
+        .and_then(|words| Mnemonic::parse(words.trim()).map_err(|err| anyhow!(err)))
+        .with_context(|| format!("invalid mnemonic in {}", mnemonic_file.display()))?;
+    let passphrase = Zeroizing::new(if with_passphrase {
+        prompt_password_stdout("Mnemonic passphrase: ")?
+    } else {
+        String::new()
+    });
+    Ok(Zeroizing::new(mnemonic.to_seed(passphrase.as_str())))
+}
+
+/// An extended private key, its private key and chain code are zeroized when dropped.
+struct ZeroizingXprv(ExtendedPrivKey);
+
+impl Drop for ZeroizingXprv {
+    fn drop(&mut self) {
+        zeroize_privkey(&mut self.0.private_key);
+        let chain_code = &mut self.0.chain_code;
+        // SAFETY: the pointer and the length are the ones of the chain code's 32 bytes array
+        let chain_code =
+            unsafe { slice::from_raw_parts_mut(chain_code.as_mut_ptr(), chain_code.len()) };
+        zeroize_slice(chain_code);
+    }
+}
+
+/// Derive the private key of the path from the seed.
+pub(crate) fn derive_privkey(seed: &[u8], path: &DerivationPath) -> Result<PrivkeyWrapper> {
+    let master = ZeroizingXprv(ExtendedPrivKey::new_master(Network::Bitcoin, seed)?);
+    let key = ZeroizingXprv(master.0.derive_priv(&SECP256K1, path)?);
+    Ok(PrivkeyWrapper(key.0.private_key))
+}
+
+/// Sign with a key derived from a mnemonic, in place of the private key.
+#[derive(Args, Default)]
+pub(crate) struct MnemonicKeyArgs {
+    /// Derive the sender private key from the BIP39 mnemonic words in the file
+    #[clap(long, value_name = "PATH")]
+    pub mnemonic_file: Option<PathBuf>,
+
+    /// Work with "--mnemonic-file", prompt for the BIP39 passphrase of the mnemonic
+    #[clap(long, requires = "mnemonic-file")]
+    pub mnemonic_passphrase: bool,
+
+    /// Work with "--mnemonic-file", the BIP32 derivation path of the sender key, the first key of the BIP44 account by default: `m/44'/309'/0'/0/0` for CKB keys and `m/44'/60'/0'/0/0` for Ethereum keys
+    #[clap(long, value_name = "PATH", requires = "mnemonic-file")]
+    pub derivation_path: Option<String>,
+}
+
+impl MnemonicKeyArgs {
+    /// The private key derived from the mnemonic, if it's given. The index of the default path
+    /// template is 0.
+    pub(crate) fn privkey(&self, default_template: &str) -> Result<Option<PrivkeyWrapper>> {
+        let mnemonic_file = match self.mnemonic_file.as_ref() {
+            Some(mnemonic_file) => mnemonic_file,
+            None => return Ok(None),
+        };
+        let path = match self.derivation_path.as_ref() {
+            Some(path) => {
+                let template = PathTemplate::new(path)?;
+                ensure!(
+                    !template.has_index(),
+                    "the derivation path of the sender key should not have the `i` level"
+                );
+                template.path(0)?
+            }
+            None => PathTemplate::new(default_template)?.path(0)?,
+        };
+        let seed = load_seed(mnemonic_file, self.mnemonic_passphrase)?;
+        derive_privkey(seed.as_ref(), &path).map(Some)
+    }
+}
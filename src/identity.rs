@@ -0,0 +1,253 @@
+use std::{fs, io::Write, path::Path};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use ckb_jsonrpc_types::JsonBytes;
+use ckb_sdk::{
+    unlock::{IdentityFlag, OmniLockConfig},
+    util::blake160,
+};
+use ckb_types::{bytes::Bytes, H160, H256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    arg_parser::{ArgParser, MultisigMemberParser, SinceArgParser},
+    build_addr::build_multisig_config,
+    flags::{admin_rc_type_id, OmniLockFlagArgs, MAX_ACP_CKB_EXP, MAX_ACP_UDT_EXP},
+    sdk_compat::{acp_config, new_omnilock_config, time_lock_since},
+    time_lock::since_string,
+    unlock::DelegateAlgorithm,
+    util::create_new_file,
+};
+
+const AUTH_FLAGS: [IdentityFlag; 10] = [
+    IdentityFlag::PubkeyHash,
+    IdentityFlag::Ethereum,
+    IdentityFlag::Eos,
+    IdentityFlag::Tron,
+    IdentityFlag::Bitcoin,
+    IdentityFlag::Dogecoin,
+    IdentityFlag::Multisig,
+    IdentityFlag::OwnerLock,
+    IdentityFlag::Exec,
+    IdentityFlag::Dl,
+];
+
+/// The name of the auth flag, the same as the `build-address` sub command of it.
+pub(crate) fn auth_flag_name(flag: IdentityFlag) -> &'static str {
+    match flag {
+        IdentityFlag::PubkeyHash => "pubkey-hash",
+        IdentityFlag::Ethereum => "ethereum",
+        IdentityFlag::Eos => "eos",
+        IdentityFlag::Tron => "tron",
+        IdentityFlag::Bitcoin => "bitcoin",
+        IdentityFlag::Dogecoin => "dogecoin",
+        IdentityFlag::Multisig => "multisig",
+        IdentityFlag::OwnerLock => "owner-lock",
+        IdentityFlag::Exec => "exec",
+        IdentityFlag::Dl => "dl",
+    }
+}
+
+pub(crate) fn auth_flag_from_name(name: &str) -> Result<IdentityFlag> {
+    AUTH_FLAGS
+        .into_iter()
+        .find(|flag| auth_flag_name(*flag) == name)
+        .ok_or_else(|| {
+            let names: Vec<_> = AUTH_FLAGS.into_iter().map(auth_flag_name).collect();
+            anyhow!(
+                "unknown auth {}, should be one of {}",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct MultisigIdentity {
+    require_first_n: u8,
+    threshold: u8,
+    /// The members in the order of the multisig config: sighash addresses, public keys or pubkey hashes
+    members: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct AcpIdentity {
+    min_ckb_exp: u8,
+    min_udt_exp: u8,
+}
+
+/// An omnilock identity, the auth and the flags, saved in a YAML or JSON file. It's written once,
+/// by `build-address --save-identity` or by hand, and then shared by build-address, generate-tx
+/// and sign, so the same lock is always built.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct IdentityFile {
+    /// The auth flag, named as the `build-address` sub command
+    auth: String,
+    /// The auth content, the hash of the preimage for exec and dl, not needed by multisig
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth_content: Option<H160>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    multisig: Option<MultisigIdentity>,
+    /// The preimage of the exec and dl auth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    preimage: Option<JsonBytes>,
+    /// The signature algorithm the exec script or the dynamic library verifies, secp256k1 if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    algorithm: Option<DelegateAlgorithm>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    admin_rc_type_id: Option<H256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    acp: Option<AcpIdentity>,
+    /// The since in the format of `--time-lock-since`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time_lock_since: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    supply_info_type_hash: Option<H256>,
+}
+
+impl IdentityFile {
+    /// The identity of the omnilock config, the flags included.
+    pub(crate) fn new(config: &OmniLockConfig, preimage: Option<&Bytes>) -> Self {
+        let flag = config.id().flag();
+        let auth_content = match flag {
+            IdentityFlag::Multisig => None,
+            _ => Some(config.id().auth_content().clone()),
+        };
+        let multisig = config
+            .multisig_config()
+            .map(|multisig_config| MultisigIdentity {
+                require_first_n: multisig_config.require_first_n(),
+                threshold: multisig_config.threshold(),
+                members: multisig_config
+                    .sighash_addresses()
+                    .iter()
+                    .map(|member| format!("{:#x}", member))
+                    .collect(),
+            });
+        IdentityFile {
+            auth: auth_flag_name(flag).to_owned(),
+            auth_content,
+            multisig,
+            preimage: preimage.cloned().map(JsonBytes::from_bytes),
+            algorithm: None,
+            admin_rc_type_id: admin_rc_type_id(config),
+            acp: acp_config(config).map(|acp| AcpIdentity {
+                min_ckb_exp: acp.ckb_minimum,
+                min_udt_exp: acp.udt_minimum,
+            }),
+            time_lock_since: time_lock_since(config).map(since_string),
+            supply_info_type_hash: config.get_info_cell().cloned(),
+        }
+    }
+
+    /// Load the identity file, in YAML or JSON.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let content =
+            fs::read(path).with_context(|| format!("try to read file {}", path.display()))?;
+        // JSON is a subset of YAML
+        serde_yaml::from_slice(&content)
+            .with_context(|| format!("try to parse identity file {}", path.display()))
+    }
+
+    /// Save the identity file, in JSON if the extension is `.json`, or in YAML. An existing file
+    /// is not overwritten.
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        let content = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::to_string_pretty(self)?
+        } else {
+            serde_yaml::to_string(self)?
+        };
+        create_new_file(path)?
+            .write_all(content.as_bytes())
+            .with_context(|| format!("try to write file {}", path.display()))
+    }
+
+    /// Set the signature algorithm of the exec and dl auth, secp256k1 is left out.
+    pub(crate) fn with_algorithm(mut self, algorithm: DelegateAlgorithm) -> Self {
+        self.algorithm = Some(algorithm).filter(|alg| *alg != DelegateAlgorithm::Secp256k1);
+        self
+    }
+
+    pub(crate) fn preimage(&self) -> Option<Bytes> {
+        self.preimage.clone().map(JsonBytes::into_bytes)
+    }
+
+    pub(crate) fn algorithm(&self) -> DelegateAlgorithm {
+        self.algorithm.unwrap_or_default()
+    }
+
+    /// The omnilock config of the auth, without any flag.
+    pub(crate) fn auth_config(&self) -> Result<OmniLockConfig> {
+        let flag = auth_flag_from_name(&self.auth)?;
+        match flag {
+            IdentityFlag::Multisig => {
+                let multisig = self
+                    .multisig
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("the multisig identity needs the multisig item"))?;
+                let members = multisig
+                    .members
+                    .iter()
+                    .map(|member| MultisigMemberParser::parse(member))
+                    .collect::<Result<Vec<_>>>()?;
+                let multisig_config =
+                    build_multisig_config(&members, multisig.require_first_n, multisig.threshold)?;
+                Ok(OmniLockConfig::new_multisig(multisig_config))
+            }
+            IdentityFlag::Exec | IdentityFlag::Dl => {
+                let preimage = self
+                    .preimage()
+                    .ok_or_else(|| anyhow!("the {} identity needs the preimage item", self.auth))?;
+                let auth_content = blake160(&preimage);
+                ensure!(
+                    self.auth_content.is_none() || self.auth_content == Some(auth_content.clone()),
+                    "the auth content does not match the preimage"
+                );
+                new_omnilock_config(flag, auth_content)
+            }
+            _ => {
+                let auth_content = self.auth_content.clone().ok_or_else(|| {
+                    anyhow!("the {} identity needs the auth-content item", self.auth)
+                })?;
+                new_omnilock_config(flag, auth_content)
+            }
+        }
+    }
+
+    /// The flags of the identity, as the options shared by all auth types.
+    pub(crate) fn flag_args(&self) -> Result<OmniLockFlagArgs> {
+        let (acp_min_ckb_exp, acp_min_udt_exp) = self
+            .acp
+            .as_ref()
+            .map_or((0, 0), |acp| (acp.min_ckb_exp, acp.min_udt_exp));
+        ensure!(
+            acp_min_ckb_exp <= MAX_ACP_CKB_EXP && acp_min_udt_exp <= MAX_ACP_UDT_EXP,
+            "the acp min-ckb-exp should not exceed {} and min-udt-exp {}",
+            MAX_ACP_CKB_EXP,
+            MAX_ACP_UDT_EXP
+        );
+        let time_lock_since = self
+            .time_lock_since
+            .as_deref()
+            .map(SinceArgParser::parse)
+            .transpose()?;
+        Ok(OmniLockFlagArgs {
+            admin_rc_type_id: self.admin_rc_type_id.clone(),
+            acp: self.acp.is_some(),
+            acp_min_ckb_exp,
+            acp_min_udt_exp,
+            time_lock_since,
+            supply_info_type_hash: self.supply_info_type_hash.clone(),
+        })
+    }
+
+    /// The full omnilock config of the identity, the flags included.
+    pub(crate) fn omnilock_config(&self) -> Result<OmniLockConfig> {
+        let mut config = self.auth_config()?;
+        self.flag_args()?.apply(&mut config)?;
+        Ok(config)
+    }
+}
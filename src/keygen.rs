@@ -0,0 +1,145 @@
+use std::{collections::BTreeMap, fs, io::Write, path::PathBuf};
+
+use anyhow::{ensure, Context, Result};
+use ckb_crypto::secp::Pubkey;
+use ckb_sdk::{
+    unlock::OmniLockConfig,
+    util::{keccak160, zeroize_slice},
+    NetworkType, SECP256K1,
+};
+use ckb_signer::{Crypto, ScryptType};
+use ckb_types::{molecule::hex_string, H160};
+use clap::Args;
+use rpassword::prompt_password_stdout;
+use serde_json::{json, Value};
+
+use crate::{
+    arg_parser::PrivkeyWrapper,
+    build_addr::{address_network, AddressEnv, OmniLockAddress},
+    client::OmniLockInfo,
+    flags::OmniLockFlagArgs,
+    keystore::CkbKeyStore,
+    util::create_new_file,
+};
+
+/// The version of the Ethereum keystore file format.
+const ETHEREUM_KEYSTORE_VERSION: u32 = 3;
+
+#[derive(Args)]
+pub(crate) struct KeygenArgs {
+    /// Export the key to an Ethereum v3 keystore file (.json) too, encrypted by the same password
+    #[clap(long, value_name = "PATH")]
+    ethereum_keystore: Option<PathBuf>,
+
+    #[clap(flatten)]
+    flag_args: OmniLockFlagArgs,
+}
+
+fn prompt_new_password() -> Result<String> {
+    let password = prompt_password_stdout("Password: ")?;
+    let repeated = prompt_password_stdout("Repeat password: ")?;
+    ensure!(password == repeated, "the passwords do not match");
+    Ok(password)
+}
+
+/// The Ethereum v3 keystore of the key, the crypto of the ckb-cli keystore is in the same
+/// format: scrypt, aes-128-ctr and the keccak256 mac.
+fn ethereum_keystore(key: &PrivkeyWrapper, address: &H160, password: &[u8]) -> Value {
+    let mut secret = key.secret_bytes();
+    let crypto = Crypto::encrypt_key_scrypt(&secret, password, ScryptType::Standard);
+    zeroize_slice(&mut secret);
+    json!({
+        "address": format!("{:x}", address),
+        "crypto": crypto.to_json(),
+        "id": uuid::Uuid::new_v4().to_hyphenated().to_string(),
+        "version": ETHEREUM_KEYSTORE_VERSION,
+    })
+}
+
+/// The omnilock address of the config as an item of the JSON output, with the auth content.
+fn address_item(
+    mut config: OmniLockConfig,
+    flag_args: &OmniLockFlagArgs,
+    auth_name: &str,
+    info: &OmniLockInfo,
+    network: Option<NetworkType>,
+) -> Result<Value> {
+    flag_args.apply(&mut config)?;
+    let mut item = serde_json::to_value(OmniLockAddress::new(&config, &info.script_id, network))?;
+    if let Value::Object(ref mut map) = item {
+        map.insert(
+            auth_name.to_owned(),
+            json!(format!("{:#x}", config.id().auth_content())),
+        );
+    }
+    Ok(item)
+}
+
+/// Generate a secp256k1 key into the ckb-cli keystore, and print its pubkey-hash and ethereum
+/// omnilock addresses.
+pub(crate) fn keygen(args: &KeygenArgs, env: &AddressEnv) -> Result<()> {
+    // Check the omnilock deployment before the key is stored
+    let info = env.config.omnilock_info()?;
+    let network = address_network(env)?;
+    // Create the Ethereum keystore file before the key is stored, an existing file is not
+    // overwritten, and it's removed if the key is not stored
+    let eth_keystore = match args.ethereum_keystore.as_ref() {
+        Some(path) => Some((path, create_new_file(path)?)),
+        None => None,
+    };
+    let stored = prompt_new_password().and_then(|password| {
+        let mut keystore = CkbKeyStore::create_default()?;
+        let (lock_arg, key) = keystore.new_key(password.as_bytes())?;
+        Ok((password, keystore, lock_arg, key))
+    });
+    let (password, keystore, lock_arg, key) = stored.inspect_err(|_| {
+        if let Some((path, _)) = eth_keystore.as_ref() {
+            let _ = fs::remove_file(path);
+        }
+    })?;
+    let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, &key);
+    let eth_address = keccak160(Pubkey::from(pubkey).as_ref());
+
+    let mut resp = BTreeMap::new();
+    resp.insert("lock-arg".to_owned(), json!(format!("{:#x}", lock_arg)));
+    resp.insert(
+        "pubkey".to_owned(),
+        json!(format!("0x{}", hex_string(&pubkey.serialize()))),
+    );
+    resp.insert(
+        "keystore-file".to_owned(),
+        json!(keystore.key_file(&lock_arg)?.to_string_lossy()),
+    );
+    resp.insert(
+        "pubkey-hash".to_owned(),
+        address_item(
+            OmniLockConfig::new_pubkey_hash(lock_arg.clone()),
+            &args.flag_args,
+            "pubkey-hash",
+            &info,
+            network,
+        )?,
+    );
+    resp.insert(
+        "ethereum".to_owned(),
+        address_item(
+            OmniLockConfig::new_ethereum(eth_address.clone()),
+            &args.flag_args,
+            "ethereum-address",
+            &info,
+            network,
+        )?,
+    );
+    if let Some((path, mut file)) = eth_keystore {
+        let content = ethereum_keystore(&key, &eth_address, password.as_bytes());
+        file.write_all(serde_json::to_string_pretty(&content)?.as_bytes())
+            .with_context(|| format!("try to write file {}", path.display()))?;
+        resp.insert(
+            "ethereum-keystore".to_owned(),
+            json!(path.to_string_lossy()),
+        );
+    }
+
+    println!("{}", serde_json::to_string_pretty(&Value::from_iter(resp))?);
+    Ok(())
+}
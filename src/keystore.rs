@@ -1,5 +1,8 @@
+use std::{fs, path::PathBuf};
+
 use anyhow::{Context, Result};
-use ckb_signer::{KeyStore, ScryptType};
+use ckb_sdk::util::zeroize_slice;
+use ckb_signer::{Key, KeyStore, MasterPrivKey, ScryptType};
 use ckb_types::H160;
 
 use crate::arg_parser::PrivkeyWrapper;
@@ -9,15 +12,55 @@ pub struct CkbKeyStore {
 }
 
 impl CkbKeyStore {
-    pub fn load_default() -> Result<Self> {
+    fn default_dir() -> PathBuf {
         let mut dir = dirs::home_dir().unwrap();
         dir.push(".ckb-cli");
         dir.push("keystore");
+        dir
+    }
+
+    pub fn load_default() -> Result<Self> {
+        let dir = Self::default_dir();
         let key_store = KeyStore::from_dir(dir.clone(), ScryptType::default())
             .with_context(|| format!("try to load from directory {}", dir.to_string_lossy()))?;
         Ok(CkbKeyStore { key_store })
     }
 
+    /// Load the default keystore, the directory is created if it does not exist yet.
+    pub fn create_default() -> Result<Self> {
+        let dir = Self::default_dir();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("try to create directory {}", dir.to_string_lossy()))?;
+        Self::load_default()
+    }
+
+    /// The lock args(blake160 of the public key) of all the keys in the keystore, sorted.
+    pub fn accounts(&mut self) -> Vec<H160> {
+        let mut accounts: Vec<H160> = self.key_store.get_accounts().keys().cloned().collect();
+        accounts.sort();
+        accounts
+    }
+
+    /// Generate a new key and store it encrypted by the password, the key is also returned to
+    /// build the addresses of it.
+    pub fn new_key(&mut self, password: &[u8]) -> Result<(H160, PrivkeyWrapper)> {
+        let master_priv_key = MasterPrivKey::try_new(1024)?;
+        let mut bytes = master_priv_key.to_bytes();
+        let key = secp256k1::SecretKey::from_slice(&bytes[0..32]);
+        zeroize_slice(&mut bytes);
+        let key = key?;
+        let hash160 = self
+            .key_store
+            .import_key(&Key::new(master_priv_key), password)
+            .context("try to store the new key")?;
+        Ok((hash160, PrivkeyWrapper(key)))
+    }
+
+    /// The path of the key file in the keystore.
+    pub fn key_file(&self, hash160: &H160) -> Result<PathBuf> {
+        Ok(self.key_store.get_filepath(hash160)?)
+    }
+
     pub fn export_priv_key(&self, hash160: &H160, password: &[u8]) -> Result<PrivkeyWrapper> {
         let master_priv_key = self
             .key_store
@@ -1,23 +1,39 @@
 mod arg_parser;
+mod batch_addr;
 mod build_addr;
 mod client;
 mod config;
+mod convert_addr;
+mod decode_addr;
+mod export;
+mod flags;
 mod generate;
+mod hd_wallet;
+mod identity;
+mod keygen;
 mod keystore;
+mod passkey;
+mod sdk_compat;
 mod sign;
 mod signer;
+mod time_lock;
 mod txinfo;
+mod unlock;
 mod util;
 
 use ckb_jsonrpc_types as json_types;
 use std::{fs, path::PathBuf};
 
 use anyhow::{Context, Result};
-use build_addr::BuildAddress;
+use build_addr::{AddressEnv, BuildAddress};
 use ckb_sdk::CkbRpcClient;
 use clap::{Parser, Subcommand};
 use config::{handle_config_cmds, ConfigCmds, ConfigContext};
+use convert_addr::{convert_omnilock_addr, ConvertAddressArgs};
+use decode_addr::{decode_omnilock_addr, DecodeAddressArgs};
+use export::ExportFormat;
 use generate::{generate_transfer_tx, GenerateTx};
+use keygen::{keygen, KeygenArgs};
 use sign::{sign_tx, SignCmd};
 
 use crate::{build_addr::build_omnilock_addr, txinfo::TxInfo};
@@ -25,8 +41,38 @@ use crate::{build_addr::build_omnilock_addr, txinfo::TxInfo};
 #[derive(Subcommand)]
 enum Commands {
     /// build omni lock address
-    #[clap(subcommand)]
-    BuildAddress(BuildAddress),
+    BuildAddress {
+        /// Save the auth and the flags of the address to a new identity file (.yaml or .json), an existing file is not overwritten
+        #[clap(long, value_name = "PATH", global = true)]
+        save_identity: Option<PathBuf>,
+        /// Print the addresses of both the mainnet and the testnet, instead of the network of the node
+        #[clap(long, global = true)]
+        all_networks: bool,
+        /// Print the lock script and the cell dep of the omnilock in the formats too, separated by comma
+        #[clap(
+            long,
+            value_name = "FORMAT",
+            global = true,
+            value_enum,
+            use_value_delimiter = true,
+            multiple_occurrences = true
+        )]
+        export: Vec<ExportFormat>,
+        #[clap(subcommand)]
+        cmds: BuildAddress,
+    },
+    /// decode an omnilock address or its args
+    DecodeAddress(DecodeAddressArgs),
+    /// convert between the sighash address and the omnilock address of the same key
+    ConvertAddress(ConvertAddressArgs),
+    /// generate a secp256k1 key into the ckb-cli keystore, and print its pubkey-hash and ethereum omnilock addresses
+    Keygen {
+        /// Print the addresses of both the mainnet and the testnet, instead of the network of the node
+        #[clap(long)]
+        all_networks: bool,
+        #[clap(flatten)]
+        args: KeygenArgs,
+    },
     /// generate a transaction not signed yet
     #[clap(subcommand)]
     GenerateTx(GenerateTx),
@@ -65,9 +111,35 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::BuildAddress(cmds) => {
+        Commands::BuildAddress {
+            save_identity,
+            all_networks,
+            export,
+            cmds,
+        } => {
+            let config = ConfigContext::parse(&cli.config)?;
+            let env = AddressEnv {
+                config: &config,
+                all_networks,
+                exports: export,
+            };
+            build_omnilock_addr(cmds, save_identity, &env)?;
+        }
+        Commands::DecodeAddress(args) => {
+            decode_omnilock_addr(&args, &cli.config)?;
+        }
+        Commands::ConvertAddress(args) => {
+            let config = ConfigContext::parse(&cli.config)?;
+            convert_omnilock_addr(&args, &config)?;
+        }
+        Commands::Keygen { all_networks, args } => {
             let config = ConfigContext::parse(&cli.config)?;
-            build_omnilock_addr(cmds, &config)?;
+            let env = AddressEnv {
+                config: &config,
+                all_networks,
+                exports: Vec::new(),
+            };
+            keygen(&args, &env)?;
         }
         Commands::GenerateTx(cmds) => {
             let config = ConfigContext::parse(&cli.config)?;
@@ -90,7 +162,7 @@ fn main() -> Result<()> {
 
 fn send_tx(tx_file: &PathBuf, env: &ConfigContext) -> Result<()> {
     // Send transaction
-    let read = fs::read(&tx_file)
+    let read = fs::read(tx_file)
         .with_context(|| format!("try to read file {}", tx_file.to_string_lossy()))?;
     let tx_info: TxInfo = serde_json::from_slice(&read)
         .with_context(|| format!("try to parse file {}", tx_file.to_string_lossy()))?;
@@ -0,0 +1,375 @@
+use std::{cell::RefCell, fs, path::Path, rc::Rc};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bitcoin::hashes::{sha256, Hash};
+use ckb_sdk::{
+    traits::{Signer, SignerError},
+    util::blake160,
+};
+use ckb_types::{
+    bytes::{BufMut, Bytes, BytesMut},
+    core::TransactionView,
+    H160,
+};
+use clap::ValueEnum;
+use p256::ecdsa::{
+    signature::{Signer as _, Verifier},
+    Signature, SigningKey, VerifyingKey,
+};
+use serde::Deserialize;
+
+use crate::unlock::{DelegateAlgorithm, WEBAUTHN_HEADER_LEN, WEBAUTHN_SIGNATURE_LEN};
+
+/// The user present flag of the authenticator data.
+const FLAG_USER_PRESENT: u8 = 0x01;
+/// The user verified flag of the authenticator data.
+const FLAG_USER_VERIFIED: u8 = 0x04;
+/// The flags of the authenticator data made by the software authenticator.
+const SOFTWARE_AUTHENTICATOR_FLAGS: u8 = FLAG_USER_PRESENT | FLAG_USER_VERIFIED;
+/// The length of the authenticator data without the attested credential data and the
+/// extensions: rpIdHash(32) | flags(1) | signCount(4).
+const AUTHENTICATOR_DATA_MIN_LEN: usize = 37;
+
+/// The signature a passkey verifier checks.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum PasskeyAlgorithm {
+    /// The WebAuthn assertion of the passkey
+    Webauthn,
+    /// The raw secp256r1 signature of the message
+    Secp256r1,
+}
+
+impl From<PasskeyAlgorithm> for DelegateAlgorithm {
+    fn from(algorithm: PasskeyAlgorithm) -> Self {
+        match algorithm {
+            PasskeyAlgorithm::Webauthn => DelegateAlgorithm::Webauthn,
+            PasskeyAlgorithm::Secp256r1 => DelegateAlgorithm::Secp256r1,
+        }
+    }
+}
+
+/// The public key x | y, the form the verifier reads from the signature.
+pub(crate) fn p256_pubkey_bytes(pubkey: &VerifyingKey) -> [u8; 64] {
+    let point = pubkey.to_encoded_point(false);
+    let mut data = [0u8; 64];
+    data.copy_from_slice(&point.as_bytes()[1..]);
+    data
+}
+
+/// The identity of the P-256 public key in the preimage, the blake160 hash of x | y.
+pub(crate) fn p256_pubkey_hash(pubkey: &VerifyingKey) -> H160 {
+    blake160(&p256_pubkey_bytes(pubkey))
+}
+
+/// The WebAuthn challenge of the signature message, in base64url without padding.
+pub(crate) fn webauthn_challenge(message: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(message)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AssertionResponse {
+    authenticator_data: String,
+    #[serde(rename = "clientDataJSON")]
+    client_data_json: String,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct AssertionCredential {
+    response: AssertionResponse,
+}
+
+#[derive(Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    type_: String,
+    challenge: String,
+}
+
+/// A WebAuthn assertion of a passkey, the signature is over the authenticator data and the
+/// SHA-256 hash of the client data JSON.
+pub(crate) struct WebAuthnAssertion {
+    authenticator_data: Vec<u8>,
+    client_data_json: Vec<u8>,
+    signature: Signature,
+}
+
+impl WebAuthnAssertion {
+    /// Load the assertion in the JSON format of `PublicKeyCredential.toJSON()`, whose binary
+    /// fields are in base64url and the signature is DER encoded.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let content =
+            fs::read(path).with_context(|| format!("try to read file {}", path.display()))?;
+        let credential: AssertionCredential = serde_json::from_slice(&content)
+            .with_context(|| format!("try to parse assertion file {}", path.display()))?;
+        let decode = |name: &str, value: &str| {
+            URL_SAFE_NO_PAD
+                .decode(value.trim_end_matches('='))
+                .map_err(|err| anyhow!("invalid base64url {} in the assertion: {}", name, err))
+        };
+        let response = credential.response;
+        let signature = decode("signature", &response.signature)?;
+        let signature = Signature::from_der(&signature)
+            .map_err(|err| anyhow!("invalid signature in the assertion: {}", err))?;
+        Ok(WebAuthnAssertion {
+            authenticator_data: decode("authenticatorData", &response.authenticator_data)?,
+            client_data_json: decode("clientDataJSON", &response.client_data_json)?,
+            signature,
+        })
+    }
+
+    /// Make the assertion of the message as a software authenticator of the relying party.
+    pub(crate) fn sign(key: &SigningKey, rp_id: &str, message: &[u8]) -> Self {
+        let mut authenticator_data = sha256::Hash::hash(rp_id.as_bytes()).to_vec();
+        authenticator_data.push(SOFTWARE_AUTHENTICATOR_FLAGS);
+        // The signature counter, always 0 for a software authenticator
+        authenticator_data.extend_from_slice(&[0u8; 4]);
+        let client_data_json = format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://{}","crossOrigin":false}}"#,
+            webauthn_challenge(message),
+            rp_id
+        )
+        .into_bytes();
+        let signature: Signature = key.sign(&signed_data(&authenticator_data, &client_data_json));
+        WebAuthnAssertion {
+            authenticator_data,
+            client_data_json,
+            signature,
+        }
+    }
+
+    /// Check the assertion is made by the public key for the relying party, with the user
+    /// present and the challenge of the message.
+    fn verify(&self, pubkey: &VerifyingKey, rp_id: &str, message: &[u8]) -> Result<()> {
+        ensure!(
+            self.authenticator_data.len() >= AUTHENTICATOR_DATA_MIN_LEN,
+            "the authenticator data of the assertion is shorter than {} bytes",
+            AUTHENTICATOR_DATA_MIN_LEN
+        );
+        ensure!(
+            self.authenticator_data[..32] == sha256::Hash::hash(rp_id.as_bytes())[..],
+            "the assertion is not made for the relying party {}",
+            rp_id
+        );
+        ensure!(
+            self.authenticator_data[32] & FLAG_USER_PRESENT != 0,
+            "the user present flag is not set in the assertion"
+        );
+        let client_data: ClientData = serde_json::from_slice(&self.client_data_json)
+            .map_err(|err| anyhow!("invalid client data JSON in the assertion: {}", err))?;
+        ensure!(
+            client_data.type_ == "webauthn.get",
+            "the assertion is not made by navigator.credentials.get"
+        );
+        let challenge = webauthn_challenge(message);
+        ensure!(
+            client_data.challenge == challenge,
+            "the challenge of the assertion is not {}, the assertion is made for another transaction",
+            challenge
+        );
+        let data = signed_data(&self.authenticator_data, &self.client_data_json);
+        pubkey
+            .verify(&data, &self.signature)
+            .map_err(|_| anyhow!("the assertion is not signed by the passkey"))
+    }
+
+    /// The signature in the witness lock, see `WEBAUTHN_SIGNATURE_LEN` for the layout.
+    fn witness_signature(&self, pubkey: &VerifyingKey) -> Result<Bytes> {
+        let data_len = self.authenticator_data.len() + self.client_data_json.len();
+        ensure!(
+            data_len + WEBAUTHN_HEADER_LEN <= WEBAUTHN_SIGNATURE_LEN,
+            "the authenticator data and the client data JSON are too long: {} bytes",
+            data_len
+        );
+        let signature = self.signature.normalize_s().unwrap_or(self.signature);
+        let mut lock = BytesMut::with_capacity(WEBAUTHN_SIGNATURE_LEN);
+        lock.put(&p256_pubkey_bytes(pubkey)[..]);
+        lock.put(&signature.to_bytes()[..]);
+        lock.put_u16_le(self.authenticator_data.len() as u16);
+        lock.put_u16_le(self.client_data_json.len() as u16);
+        lock.put(&self.authenticator_data[..]);
+        lock.put(&self.client_data_json[..]);
+        lock.resize(WEBAUTHN_SIGNATURE_LEN, 0);
+        Ok(lock.freeze())
+    }
+}
+
+/// authenticator data | sha256(client data JSON), the data signed by the authenticator.
+fn signed_data(authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+    let mut data = authenticator_data.to_vec();
+    data.extend_from_slice(&sha256::Hash::hash(client_data_json)[..]);
+    data
+}
+
+/// Where the passkey signature comes from.
+pub(crate) enum PasskeyAuthenticator {
+    /// The P-256 private key, the assertion is made by the software authenticator of the relying party id
+    Key { key: SigningKey, rp_id: String },
+    /// The assertion made by an authenticator of the relying party id for the challenge of the transaction
+    Assertion {
+        assertion: WebAuthnAssertion,
+        rp_id: String,
+    },
+    /// Nothing is signed, the message is kept to print the challenge of the transaction
+    Challenge(Rc<RefCell<Option<Bytes>>>),
+}
+
+/// The signer of the secp256r1 and WebAuthn verifiers, the id is the blake160 hash of the
+/// P-256 public key.
+pub(crate) struct PasskeySigner {
+    pubkey: VerifyingKey,
+    algorithm: DelegateAlgorithm,
+    authenticator: PasskeyAuthenticator,
+}
+
+impl PasskeySigner {
+    pub(crate) fn new(
+        pubkey: VerifyingKey,
+        algorithm: DelegateAlgorithm,
+        authenticator: PasskeyAuthenticator,
+    ) -> Result<Self> {
+        ensure!(
+            matches!(
+                algorithm,
+                DelegateAlgorithm::Secp256r1 | DelegateAlgorithm::Webauthn
+            ),
+            "the passkey only signs for the secp256r1 and webauthn verifiers"
+        );
+        ensure!(
+            algorithm == DelegateAlgorithm::Webauthn
+                || matches!(authenticator, PasskeyAuthenticator::Key { .. }),
+            "the secp256r1 verifier needs the signature of the P-256 private key"
+        );
+        Ok(PasskeySigner {
+            pubkey,
+            algorithm,
+            authenticator,
+        })
+    }
+}
+
+impl Signer for PasskeySigner {
+    fn match_id(&self, id: &[u8]) -> bool {
+        id.len() == 20 && id == p256_pubkey_hash(&self.pubkey).as_bytes()
+    }
+
+    fn sign(
+        &self,
+        id: &[u8],
+        message: &[u8],
+        _recoverable: bool,
+        _tx: &TransactionView,
+    ) -> Result<Bytes, SignerError> {
+        if !self.match_id(id) {
+            return Err(SignerError::IdNotFound);
+        }
+        let signature = match (&self.authenticator, self.algorithm) {
+            (PasskeyAuthenticator::Key { key, .. }, DelegateAlgorithm::Secp256r1) => {
+                let signature: Signature = key.sign(message);
+                let signature = signature.normalize_s().unwrap_or(signature);
+                let mut data = p256_pubkey_bytes(&self.pubkey).to_vec();
+                data.extend_from_slice(&signature.to_bytes());
+                Bytes::from(data)
+            }
+            (PasskeyAuthenticator::Key { key, rp_id }, _) => {
+                WebAuthnAssertion::sign(key, rp_id, message).witness_signature(&self.pubkey)?
+            }
+            (PasskeyAuthenticator::Assertion { assertion, rp_id }, _) => {
+                assertion.verify(&self.pubkey, rp_id, message)?;
+                assertion.witness_signature(&self.pubkey)?
+            }
+            (PasskeyAuthenticator::Challenge(challenge), _) => {
+                *challenge.borrow_mut() = Some(Bytes::from(message.to_vec()));
+                Bytes::from(vec![0u8; WEBAUTHN_SIGNATURE_LEN])
+            }
+        };
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unlock::SECP256R1_SIGNATURE_LEN;
+    use ckb_types::core::TransactionBuilder;
+
+    const RP_ID: &str = "localhost";
+    const MESSAGE: [u8; 32] = [0x22; 32];
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[0x11; 32].into()).unwrap()
+    }
+
+    #[test]
+    fn test_software_authenticator_assertion() {
+        let key = signing_key();
+        let pubkey = *key.verifying_key();
+        let assertion = WebAuthnAssertion::sign(&key, RP_ID, &MESSAGE);
+        assertion.verify(&pubkey, RP_ID, &MESSAGE).unwrap();
+        assert!(assertion.verify(&pubkey, "example.com", &MESSAGE).is_err());
+        assert!(assertion.verify(&pubkey, RP_ID, &[0x33; 32]).is_err());
+        let other_key = SigningKey::from_bytes(&[0x12; 32].into()).unwrap();
+        assert!(assertion
+            .verify(other_key.verifying_key(), RP_ID, &MESSAGE)
+            .is_err());
+
+        let mut not_present = WebAuthnAssertion::sign(&key, RP_ID, &MESSAGE);
+        not_present.authenticator_data[32] &= !FLAG_USER_PRESENT;
+        let err = not_present.verify(&pubkey, RP_ID, &MESSAGE).unwrap_err();
+        assert!(err.to_string().contains("user present"), "{}", err);
+    }
+
+    #[test]
+    fn test_webauthn_witness_signature() {
+        let key = signing_key();
+        let pubkey = *key.verifying_key();
+        let signer = PasskeySigner::new(
+            pubkey,
+            DelegateAlgorithm::Webauthn,
+            PasskeyAuthenticator::Key {
+                key,
+                rp_id: RP_ID.to_owned(),
+            },
+        )
+        .unwrap();
+        let id = p256_pubkey_hash(&pubkey);
+        let tx = TransactionBuilder::default().build();
+        let lock = signer.sign(id.as_bytes(), &MESSAGE, false, &tx).unwrap();
+        assert_eq!(lock.len(), WEBAUTHN_SIGNATURE_LEN);
+        assert_eq!(&lock[..64], &p256_pubkey_bytes(&pubkey)[..]);
+
+        let data_len = u16::from_le_bytes([lock[128], lock[129]]) as usize;
+        let json_len = u16::from_le_bytes([lock[130], lock[131]]) as usize;
+        let data_end = WEBAUTHN_HEADER_LEN + data_len;
+        let json_end = data_end + json_len;
+        assert!(lock[json_end..].iter().all(|byte| *byte == 0));
+        let assertion = WebAuthnAssertion {
+            authenticator_data: lock[WEBAUTHN_HEADER_LEN..data_end].to_vec(),
+            client_data_json: lock[data_end..json_end].to_vec(),
+            signature: Signature::from_slice(&lock[64..128]).unwrap(),
+        };
+        assertion.verify(&pubkey, RP_ID, &MESSAGE).unwrap();
+    }
+
+    #[test]
+    fn test_secp256r1_witness_signature() {
+        let key = signing_key();
+        let pubkey = *key.verifying_key();
+        let authenticator = PasskeyAuthenticator::Key {
+            key,
+            rp_id: RP_ID.to_owned(),
+        };
+        let signer =
+            PasskeySigner::new(pubkey, DelegateAlgorithm::Secp256r1, authenticator).unwrap();
+        let id = p256_pubkey_hash(&pubkey);
+        let tx = TransactionBuilder::default().build();
+        let lock = signer.sign(id.as_bytes(), &MESSAGE, false, &tx).unwrap();
+        assert_eq!(lock.len(), SECP256R1_SIGNATURE_LEN);
+        assert_eq!(&lock[..64], &p256_pubkey_bytes(&pubkey)[..]);
+        let signature = Signature::from_slice(&lock[64..]).unwrap();
+        assert!(signature.normalize_s().is_none());
+        pubkey.verify(&MESSAGE, &signature).unwrap();
+    }
+}
@@ -0,0 +1,99 @@
+//! The fields of ckb-sdk's `OmniLockConfig` it has no public API for, read and written through
+//! its serde form. The field names are the ones of ckb-sdk 2.3.0, which is pinned in Cargo.toml;
+//! check `ckb_sdk::unlock::omni_lock` and run the tests below before upgrading it.
+
+use anyhow::Result;
+use ckb_sdk::{
+    types::xudt_rce_mol::SmtProofEntryVec,
+    unlock::{IdentityFlag, OmniLockAcpConfig, OmniLockConfig},
+};
+use ckb_types::{H160, H256};
+use serde_json::json;
+
+use crate::flags::ADMIN_FLAG;
+
+/// Create an omnilock config with any auth flag. `OmniLockConfig::new` clears the auth content
+/// of the flags ckb-sdk can't sign, so the identity is filled in through serde instead.
+pub fn new_omnilock_config(flag: IdentityFlag, auth_content: H160) -> Result<OmniLockConfig> {
+    let mut config = serde_json::to_value(OmniLockConfig::new_pubkey_hash(auth_content))?;
+    config["id"]["flag"] = serde_json::to_value(flag)?;
+    Ok(serde_json::from_value(config)?)
+}
+
+/// Enable the administrator mode with the RC cell's type ID. The admin config of ckb-sdk is not
+/// exported, so it's filled in through serde. The administrator auth is the same as the normal one.
+pub(crate) fn set_admin_rc_type_id(config: &mut OmniLockConfig, rc_type_id: &H256) -> Result<()> {
+    let mut value = serde_json::to_value(&*config)?;
+    value["admin_config"] = json!({
+        "rc_type_id": rc_type_id,
+        "proofs": SmtProofEntryVec::default(),
+        "auth": value["id"].clone(),
+        "multisig_config": value["multisig_config"].clone(),
+        "rce_in_input": false,
+    });
+    let bits = value["omni_lock_flags"]["bits"]
+        .as_u64()
+        .unwrap_or_default() as u8;
+    value["omni_lock_flags"]["bits"] = json!(bits | ADMIN_FLAG);
+    *config = serde_json::from_value(value)?;
+    Ok(())
+}
+
+/// The minimum exponents of CKB and UDT of the anyone-can-pay mode, if it's enabled.
+pub(crate) fn acp_config(config: &OmniLockConfig) -> Option<OmniLockAcpConfig> {
+    let mut value = serde_json::to_value(config).ok()?;
+    serde_json::from_value(value["acp_config"].take()).ok()?
+}
+
+/// The since value of the time-lock mode, if it's enabled.
+pub(crate) fn time_lock_since(config: &OmniLockConfig) -> Option<u64> {
+    let value = serde_json::to_value(config).ok()?;
+    value["time_lock_config"].as_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUTH_CONTENT: [u8; 20] = [0x11; 20];
+
+    #[test]
+    fn test_new_omnilock_config() {
+        let config = new_omnilock_config(IdentityFlag::Dl, H160(AUTH_CONTENT)).unwrap();
+        assert_eq!(config.id().flag(), IdentityFlag::Dl);
+        assert_eq!(config.id().auth_content(), &H160(AUTH_CONTENT));
+
+        let mut args = vec![IdentityFlag::Dl as u8];
+        args.extend_from_slice(&AUTH_CONTENT);
+        args.push(0);
+        assert_eq!(config.build_args().as_ref(), args.as_slice());
+    }
+
+    #[test]
+    fn test_set_admin_rc_type_id() {
+        let rc_type_id = H256([0x22; 32]);
+        let mut config = OmniLockConfig::new_pubkey_hash(H160(AUTH_CONTENT));
+        set_admin_rc_type_id(&mut config, &rc_type_id).unwrap();
+        let admin_config = config.get_admin_config().unwrap();
+        assert_eq!(admin_config.rc_type_id(), &rc_type_id);
+
+        let mut args = vec![IdentityFlag::PubkeyHash as u8];
+        args.extend_from_slice(&AUTH_CONTENT);
+        args.push(ADMIN_FLAG);
+        args.extend_from_slice(rc_type_id.as_bytes());
+        assert_eq!(config.build_args().as_ref(), args.as_slice());
+    }
+
+    #[test]
+    fn test_acp_config_and_time_lock_since() {
+        let mut config = OmniLockConfig::new_pubkey_hash(H160(AUTH_CONTENT));
+        assert!(acp_config(&config).is_none());
+        assert!(time_lock_since(&config).is_none());
+
+        config.set_acp_config(OmniLockAcpConfig::new(3, 5));
+        config.set_time_lock_config(0x2000_0000_0000_0064);
+        let acp = acp_config(&config).unwrap();
+        assert_eq!((acp.ckb_minimum, acp.udt_minimum), (3, 5));
+        assert_eq!(time_lock_since(&config), Some(0x2000_0000_0000_0064));
+    }
+}
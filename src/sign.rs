@@ -2,34 +2,53 @@ use ckb_crypto::secp::Pubkey;
 use ckb_hash::blake2b_256;
 use ckb_jsonrpc_types as json_types;
 use ckb_sdk::{
+    constants::{MULTISIG_TYPE_HASH, SIGHASH_TYPE_HASH},
     traits::DefaultTransactionDependencyProvider,
+    traits::{SecpCkbRawKeySigner, Signer},
     tx_builder::unlock_tx,
     types::omni_lock::OmniLockWitnessLock,
-    unlock::{OmniLockConfig, OmniUnlockMode},
+    unlock::{
+        IdentityFlag, OmniLockConfig, OmniUnlockMode, ScriptUnlocker, SecpMultisigUnlocker,
+        SecpSighashUnlocker,
+    },
     util::keccak160,
-    ScriptGroup, SECP256K1,
+    ScriptGroup, ScriptId, SECP256K1,
 };
 use ckb_types::{
+    bytes::Bytes,
     core::TransactionView,
     molecule::hex_string,
     packed::{Transaction, WitnessArgs},
     prelude::*,
     H160,
 };
-use clap::{Args, Subcommand};
+use clap::{ArgGroup, Args, Subcommand};
+use p256::ecdsa::{SigningKey, VerifyingKey};
 use rpassword::prompt_password_stdout;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 use crate::{
-    arg_parser::{ArgParser, PrivkeyArgParser, PrivkeyWrapper},
-    client::build_omnilock_cell_dep,
+    arg_parser::{
+        ArgParser, Ed25519PrivkeyParser, P256PrivkeyParser, P256PubkeyParser, PrivkeyArgParser,
+        PrivkeyWrapper, WifPrivkeyArgParser,
+    },
     config::ConfigContext,
-    generate::build_omnilock_unlockers,
+    generate::{
+        build_omnilock_signer, build_omnilock_unlockers, build_omnilock_unlockers_with_signer,
+        SignerKeys,
+    },
+    hd_wallet::{MnemonicKeyArgs, CKB_PATH_TEMPLATE, ETHEREUM_PATH_TEMPLATE},
+    identity::{auth_flag_name, IdentityFile},
     keystore::CkbKeyStore,
+    passkey::{webauthn_challenge, PasskeyAuthenticator, PasskeySigner, WebAuthnAssertion},
     txinfo::TxInfo,
+    unlock::{signer_id, zero_lock, DelegateAlgorithm},
 };
-use anyhow::{bail, Result};
+use anyhow::{bail, ensure, Result};
 
 #[derive(Args)]
 pub struct SignTxPubkeyHashArgs {
@@ -39,15 +58,35 @@ pub struct SignTxPubkeyHashArgs {
     /// the unlock account
     #[clap(long, value_name = "ACCOUNT", value_parser=H160::parse)]
     pub from_account: Option<H160>,
+    #[clap(flatten)]
+    pub mnemonic_args: MnemonicKeyArgs,
     /// The output transaction info file (.json)
     #[clap(long, value_name = "PATH")]
     pub tx_file: PathBuf,
 }
 
 #[derive(Args)]
+#[clap(group(
+    ArgGroup::new("key")
+        .required(true)
+        .args(&["sender-key", "mnemonic-file"]),
+))]
 pub struct EthereumArgs {
     /// The sender private key (hex string)
     #[clap(long, value_name = "KEY", value_parser=PrivkeyArgParser::parse)]
+    sender_key: Option<PrivkeyWrapper>,
+    #[clap(flatten)]
+    mnemonic_args: MnemonicKeyArgs,
+
+    /// The output transaction info file (.json)
+    #[clap(long, value_name = "PATH")]
+    tx_file: PathBuf,
+}
+
+#[derive(Args)]
+pub struct SignTxSingleKeyArgs {
+    /// The sender private key (hex string or WIF)
+    #[clap(long, value_name = "KEY", value_parser=WifPrivkeyArgParser::parse)]
     sender_key: PrivkeyWrapper,
 
     /// The output transaction info file (.json)
@@ -66,27 +105,230 @@ pub struct SignTxMultisigArgs {
     tx_file: PathBuf,
 }
 
+#[derive(Args)]
+pub struct SignTxOwnerLockArgs {
+    /// The private key(s) of the owner lock, the sighash key or the multisig members' keys (hex string)
+    #[clap(long, value_name = "KEY", multiple_values = true, value_parser=PrivkeyArgParser::parse)]
+    sender_key: Vec<PrivkeyWrapper>,
+
+    /// The output transaction info file (.json)
+    #[clap(long, value_name = "PATH")]
+    tx_file: PathBuf,
+}
+
+#[derive(Args)]
+pub struct SignTxAcpDepositArgs {
+    /// The depositor's private key (hex string)
+    #[clap(long, value_name = "KEY", value_parser=PrivkeyArgParser::parse)]
+    sender_key: PrivkeyWrapper,
+
+    /// The output transaction info file (.json)
+    #[clap(long, value_name = "PATH")]
+    tx_file: PathBuf,
+}
+
+#[derive(Args)]
+pub struct SignTxIdentityArgs {
+    /// The identity file (.yaml or .json) of the omnilock cell to sign
+    #[clap(long, value_name = "PATH")]
+    identity: PathBuf,
+
+    /// The private key(s) of the identity, the members' keys of a multisig identity (hex string or WIF)
+    #[clap(long, value_name = "KEY", multiple_values = true, required = true, value_parser=WifPrivkeyArgParser::parse)]
+    sender_key: Vec<PrivkeyWrapper>,
+
+    /// The output transaction info file (.json)
+    #[clap(long, value_name = "PATH")]
+    tx_file: PathBuf,
+}
+
+#[derive(Args)]
+#[clap(group(
+    ArgGroup::new("authenticator")
+        .required(true)
+        .args(&["p256-key", "assertion", "challenge"]),
+))]
+pub struct SignTxPasskeyArgs {
+    /// The P-256 private key of the passkey (hex string), the WebAuthn assertion is made by a software authenticator
+    #[clap(long, value_name = "KEY", value_parser=P256PrivkeyParser::parse)]
+    p256_key: Option<SigningKey>,
+
+    /// The WebAuthn assertion file (.json) of the transaction challenge, in the format of `PublicKeyCredential.toJSON()`
+    #[clap(long, value_name = "PATH", requires = "p256-pubkey")]
+    assertion: Option<PathBuf>,
+
+    /// Print the WebAuthn challenge of the transaction to get the assertion from an authenticator, nothing is signed
+    #[clap(long, requires = "p256-pubkey")]
+    challenge: bool,
+
+    /// The P-256 public key of the passkey (hex string), needed by --assertion and --challenge
+    #[clap(long, value_name = "PUB_KEY", value_parser=P256PubkeyParser::parse)]
+    p256_pubkey: Option<VerifyingKey>,
+
+    /// The relying party id of the software authenticator, or the one the assertion is made for
+    #[clap(long, value_name = "RP_ID", default_value = "localhost")]
+    rp_id: String,
+
+    /// The output transaction info file (.json)
+    #[clap(long, value_name = "PATH")]
+    tx_file: PathBuf,
+}
+
+#[derive(Args)]
+pub struct SignTxEd25519Args {
+    /// The ed25519 private key, the 32 bytes seed in hex string or the Solana keypair in base58
+    #[clap(long, value_name = "KEY", value_parser=Ed25519PrivkeyParser::parse)]
+    ed25519_key: ed25519_dalek::SigningKey,
+
+    /// The output transaction info file (.json)
+    #[clap(long, value_name = "PATH")]
+    tx_file: PathBuf,
+}
+
 #[derive(Subcommand)]
 pub enum SignCmd {
     /// to sign a transaction from pubkey hash omnilock cell
     PubkeyHash(SignTxPubkeyHashArgs),
     /// to sign a transaction from ethereum omnilock cell
     Ethereum(EthereumArgs),
+    /// to sign a transaction from EOS omnilock cell
+    Eos(SignTxSingleKeyArgs),
+    /// to sign a transaction from tron omnilock cell
+    Tron(SignTxSingleKeyArgs),
+    /// to sign a transaction from bitcoin omnilock cell
+    Bitcoin(SignTxSingleKeyArgs),
+    /// to sign a transaction from dogecoin omnilock cell
+    Dogecoin(SignTxSingleKeyArgs),
     /// to sign a transaction from multisig omnilock cell
     Multisig(SignTxMultisigArgs),
+    /// to sign a transaction from owner lock omnilock cell, by signing the owner's input
+    OwnerLock(SignTxOwnerLockArgs),
+    /// to sign a transaction from exec omnilock cell, the signature is verified by the exec script
+    Exec(SignTxSingleKeyArgs),
+    /// to sign a transaction from dynamic linking omnilock cell, the signature is verified by the dynamic library
+    Dl(SignTxSingleKeyArgs),
+    /// to sign a transaction from exec or dynamic linking omnilock cell with a passkey, the secp256r1 signature or the WebAuthn assertion is verified by the delegated script
+    Passkey(SignTxPasskeyArgs),
+    /// to sign a transaction from exec or dynamic linking omnilock cell with an ed25519 key, such as a Solana key, the signature is verified by the delegated script
+    Ed25519(SignTxEd25519Args),
+    /// to sign a transaction from the omnilock cell of an identity file
+    Identity(SignTxIdentityArgs),
+    /// to sign a transaction depositing into an anyone-can-pay omnilock cell, only the depositor's inputs are signed
+    AcpDeposit(SignTxAcpDepositArgs),
 }
 
 pub fn sign_tx(cmds: &SignCmd, env: &ConfigContext) -> Result<()> {
     match cmds {
         SignCmd::PubkeyHash(args) => sign_pubkey_hash_tx(args, env),
         SignCmd::Ethereum(args) => sign_ethereum_tx(args, env),
+        SignCmd::Eos(args) => sign_single_key_tx(args, IdentityFlag::Eos, env),
+        SignCmd::Tron(args) => sign_single_key_tx(args, IdentityFlag::Tron, env),
+        SignCmd::Bitcoin(args) => sign_single_key_tx(args, IdentityFlag::Bitcoin, env),
+        SignCmd::Dogecoin(args) => sign_single_key_tx(args, IdentityFlag::Dogecoin, env),
+        SignCmd::Exec(args) => sign_single_key_tx(args, IdentityFlag::Exec, env),
+        SignCmd::Dl(args) => sign_single_key_tx(args, IdentityFlag::Dl, env),
         SignCmd::Multisig(args) => sign_multisig_tx(args, env),
+        SignCmd::OwnerLock(args) => sign_ownerlock_tx(args, env),
+        SignCmd::Passkey(args) => sign_passkey_tx(args, env),
+        SignCmd::Ed25519(args) => sign_ed25519_tx(args, env),
+        SignCmd::Identity(args) => sign_identity_tx(args, env),
+        SignCmd::AcpDeposit(args) => sign_acp_deposit_tx(args, env),
     }
 }
 
+/// Check the transaction is built for the identity, then sign it as the auth type of it.
+fn sign_identity_tx(args: &SignTxIdentityArgs, env: &ConfigContext) -> Result<()> {
+    let config = IdentityFile::load(&args.identity)?.omnilock_config()?;
+    let tx_info: TxInfo = serde_json::from_slice(&fs::read(&args.tx_file)?)?;
+    ensure!(
+        tx_info.omnilock_config.build_args() == config.build_args(),
+        "the transaction is not built for the omnilock of identity {}",
+        args.identity.display()
+    );
+    let tx_file = args.tx_file.clone();
+    let sender_key = args.sender_key.clone();
+    match config.id().flag() {
+        IdentityFlag::Multisig => sign_multisig_tx(
+            &SignTxMultisigArgs {
+                sender_key,
+                tx_file,
+            },
+            env,
+        ),
+        IdentityFlag::OwnerLock => sign_ownerlock_tx(
+            &SignTxOwnerLockArgs {
+                sender_key,
+                tx_file,
+            },
+            env,
+        ),
+        flag => {
+            ensure!(
+                sender_key.len() == 1,
+                "the {} identity is signed by exactly one key",
+                auth_flag_name(flag)
+            );
+            let sender_key = sender_key[0].clone();
+            match flag {
+                IdentityFlag::PubkeyHash => {
+                    let args = SignTxPubkeyHashArgs {
+                        sender_key: Some(sender_key),
+                        from_account: None,
+                        mnemonic_args: MnemonicKeyArgs::default(),
+                        tx_file,
+                    };
+                    sign_pubkey_hash_tx(&args, env)
+                }
+                IdentityFlag::Ethereum => sign_ethereum_tx(
+                    &EthereumArgs {
+                        sender_key: Some(sender_key),
+                        mnemonic_args: MnemonicKeyArgs::default(),
+                        tx_file,
+                    },
+                    env,
+                ),
+                _ => sign_single_key_tx(
+                    &SignTxSingleKeyArgs {
+                        sender_key,
+                        tx_file,
+                    },
+                    flag,
+                    env,
+                ),
+            }
+        }
+    }
+}
+
+/// The `sign` sub command of the transaction, by its auth type and the verifier algorithm.
+fn sign_cmd_name(tx_info: &TxInfo) -> &'static str {
+    let flag = tx_info.omnilock_config.id().flag();
+    match (flag, tx_info.algorithm()) {
+        (
+            IdentityFlag::Exec | IdentityFlag::Dl,
+            DelegateAlgorithm::Secp256r1 | DelegateAlgorithm::Webauthn,
+        ) => "passkey",
+        (IdentityFlag::Exec | IdentityFlag::Dl, DelegateAlgorithm::Ed25519) => "ed25519",
+        _ => auth_flag_name(flag),
+    }
+}
+
+/// Check the transaction is signed by the sub command, the error names the one to sign it with.
+fn ensure_sign_cmd(tx_info: &TxInfo, cmd: &str) -> Result<()> {
+    let expected = sign_cmd_name(tx_info);
+    ensure!(
+        expected == cmd,
+        "the transaction is not built for `sign {}`, sign it with `sign {}`",
+        cmd,
+        expected
+    );
+    Ok(())
+}
+
 fn sign_pubkey_hash_tx(args: &SignTxPubkeyHashArgs, env: &ConfigContext) -> Result<()> {
     let tx_info: TxInfo = serde_json::from_slice(&fs::read(&args.tx_file)?)?;
-    let tx = Transaction::from(tx_info.transaction).into_view();
+    ensure_sign_cmd(&tx_info, "pubkey-hash")?;
+    let tx = Transaction::from(tx_info.transaction.clone()).into_view();
 
     let key = if let Some(sender_key) = &args.sender_key {
         sender_key.clone()
@@ -95,8 +337,10 @@ fn sign_pubkey_hash_tx(args: &SignTxPubkeyHashArgs, env: &ConfigContext) -> Resu
         let pass = prompt_password_stdout(format!("{}: ", prompt).as_str())?;
 
         CkbKeyStore::load_default()?.export_priv_key(from_account, pass.as_bytes())?
+    } else if let Some(key) = args.mnemonic_args.privkey(CKB_PATH_TEMPLATE)? {
+        key
     } else {
-        bail!("must provide one of sender_key(private key) or an account!");
+        bail!("must provide one of sender_key(private key), an account or a mnemonic!");
     };
     let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, &key);
     let hash160 = &blake2b_256(&pubkey.serialize()[..])[0..20];
@@ -106,28 +350,27 @@ fn sign_pubkey_hash_tx(args: &SignTxPubkeyHashArgs, env: &ConfigContext) -> Resu
             hex_string(hash160)
         );
     }
-    let (tx, still_locked_groups) = sign_tx_(tx, &tx_info.omnilock_config, vec![key], env)?;
-    let witness_args = WitnessArgs::from_slice(tx.witnesses().get(0).unwrap().raw_data().as_ref())?;
-    let lock_field = witness_args.lock().to_opt().unwrap().raw_data();
-    if lock_field != tx_info.omnilock_config.zero_lock(OmniUnlockMode::Normal)?
-        && still_locked_groups.is_empty()
-    {
-        println!("> transaction ready to send!");
-    } else {
-        bail!("Failed to sign the transaction!");
-    }
-    let tx_info = TxInfo {
-        transaction: json_types::Transaction::from(tx.data()),
-        omnilock_config: tx_info.omnilock_config,
-    };
-    fs::write(&args.tx_file, serde_json::to_string_pretty(&tx_info)?)?;
-    Ok(())
+    let (tx, still_locked_groups) = sign_tx_(
+        tx,
+        &tx_info.omnilock_config,
+        None,
+        DelegateAlgorithm::Secp256k1,
+        SignerKeys::secp256k1(vec![key]),
+        env,
+    )?;
+    let zero_lock = tx_info.omnilock_config.zero_lock(OmniUnlockMode::Normal)?;
+    finish_signing(tx, tx_info, zero_lock, &still_locked_groups, &args.tx_file)
 }
 
 fn sign_ethereum_tx(args: &EthereumArgs, env: &ConfigContext) -> Result<()> {
     let tx_info: TxInfo = serde_json::from_slice(&fs::read(&args.tx_file)?)?;
-    let tx = Transaction::from(tx_info.transaction).into_view();
-    let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, &args.sender_key);
+    ensure_sign_cmd(&tx_info, "ethereum")?;
+    let tx = Transaction::from(tx_info.transaction.clone()).into_view();
+    let sender_key = match args.sender_key.clone() {
+        Some(sender_key) => sender_key,
+        None => args.mnemonic_args.privkey(ETHEREUM_PATH_TEMPLATE)?.unwrap(),
+    };
+    let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, &sender_key);
     let pubkey = Pubkey::from(pubkey);
     let hash160 = keccak160(pubkey.as_ref());
     if tx_info.omnilock_config.id().auth_content().as_bytes() != hash160.as_bytes() {
@@ -136,37 +379,148 @@ fn sign_ethereum_tx(args: &EthereumArgs, env: &ConfigContext) -> Result<()> {
     let (tx, still_locked_groups) = sign_tx_(
         tx,
         &tx_info.omnilock_config,
-        vec![args.sender_key.clone()],
+        None,
+        DelegateAlgorithm::Secp256k1,
+        SignerKeys::secp256k1(vec![sender_key]),
         env,
     )?;
-    let witness_args = WitnessArgs::from_slice(tx.witnesses().get(0).unwrap().raw_data().as_ref())?;
-    let lock_field = witness_args.lock().to_opt().unwrap().raw_data();
-    if lock_field != tx_info.omnilock_config.zero_lock(OmniUnlockMode::Normal)?
-        && still_locked_groups.is_empty()
-    {
-        println!("> transaction ready to send!");
-    } else {
-        bail!("Failed to sign the transaction!");
+    let zero_lock = tx_info.omnilock_config.zero_lock(OmniUnlockMode::Normal)?;
+    finish_signing(tx, tx_info, zero_lock, &still_locked_groups, &args.tx_file)
+}
+
+/// Sign the transaction of the auth type whose auth content is derived from a single private key.
+fn sign_single_key_tx(
+    args: &SignTxSingleKeyArgs,
+    flag: IdentityFlag,
+    env: &ConfigContext,
+) -> Result<()> {
+    let tx_info: TxInfo = serde_json::from_slice(&fs::read(&args.tx_file)?)?;
+    ensure_sign_cmd(&tx_info, auth_flag_name(flag))?;
+    let preimage = tx_info.preimage();
+    let algorithm = tx_info.algorithm();
+    let tx = Transaction::from(tx_info.transaction.clone()).into_view();
+    let id = signer_id(&tx_info.omnilock_config, preimage.as_ref())?;
+    let keys = SignerKeys::secp256k1(vec![args.sender_key.clone()]);
+    let signer = build_omnilock_signer(keys.clone(), &tx_info.omnilock_config, algorithm);
+    if !signer.match_id(id.as_bytes()) {
+        bail!(
+            "the sender key does not match hash {:#x} in omnilock config",
+            id
+        );
     }
-    let tx_info = TxInfo {
-        transaction: json_types::Transaction::from(tx.data()),
-        omnilock_config: tx_info.omnilock_config,
+    let (tx, still_locked_groups) = sign_tx_(
+        tx,
+        &tx_info.omnilock_config,
+        preimage.clone(),
+        algorithm,
+        keys,
+        env,
+    )?;
+    let zero_lock = zero_lock(&tx_info.omnilock_config, preimage.as_ref(), algorithm)?;
+    finish_signing(tx, tx_info, zero_lock, &still_locked_groups, &args.tx_file)
+}
+
+/// Sign the transaction of a secp256r1 or WebAuthn verifier with the passkey.
+fn sign_passkey_tx(args: &SignTxPasskeyArgs, env: &ConfigContext) -> Result<()> {
+    let tx_info: TxInfo = serde_json::from_slice(&fs::read(&args.tx_file)?)?;
+    ensure_sign_cmd(&tx_info, "passkey")?;
+    let preimage = tx_info.preimage();
+    let algorithm = tx_info.algorithm();
+    let tx = Transaction::from(tx_info.transaction.clone()).into_view();
+    let id = signer_id(&tx_info.omnilock_config, preimage.as_ref())?;
+    let challenge = Rc::new(RefCell::new(None));
+    let (pubkey, authenticator) = match (args.p256_key.as_ref(), args.p256_pubkey) {
+        (Some(key), _) => {
+            let authenticator = PasskeyAuthenticator::Key {
+                key: key.clone(),
+                rp_id: args.rp_id.clone(),
+            };
+            (*key.verifying_key(), authenticator)
+        }
+        (None, Some(pubkey)) => {
+            let authenticator = match args.assertion.as_ref() {
+                Some(path) => PasskeyAuthenticator::Assertion {
+                    assertion: WebAuthnAssertion::load(path)?,
+                    rp_id: args.rp_id.clone(),
+                },
+                None => PasskeyAuthenticator::Challenge(challenge.clone()),
+            };
+            (pubkey, authenticator)
+        }
+        (None, None) => bail!("must provide the P-256 private key or the public key"),
     };
-    fs::write(&args.tx_file, serde_json::to_string_pretty(&tx_info)?)?;
-    Ok(())
+    let signer = PasskeySigner::new(pubkey, algorithm, authenticator)?;
+    if !signer.match_id(id.as_bytes()) {
+        bail!(
+            "the passkey does not match hash {:#x} in omnilock config",
+            id
+        );
+    }
+
+    let tx_dep_provider = DefaultTransactionDependencyProvider::new(env.ckb_rpc.as_str(), 10);
+    let cell = env.omnilock_info()?;
+    let unlockers = build_omnilock_unlockers_with_signer(
+        Box::new(signer),
+        tx_info.omnilock_config.clone(),
+        preimage.clone(),
+        algorithm,
+        cell.script_id,
+    );
+    let (tx, still_locked_groups) = unlock_tx(tx, &tx_dep_provider, &unlockers)?;
+    if let Some(message) = challenge.borrow().as_ref() {
+        println!("{}", webauthn_challenge(message));
+        return Ok(());
+    }
+    let zero_lock = zero_lock(&tx_info.omnilock_config, preimage.as_ref(), algorithm)?;
+    finish_signing(tx, tx_info, zero_lock, &still_locked_groups, &args.tx_file)
+}
+
+/// Sign the transaction of the ed25519 verifier, the id is the blake160 hash of the public key.
+fn sign_ed25519_tx(args: &SignTxEd25519Args, env: &ConfigContext) -> Result<()> {
+    let tx_info: TxInfo = serde_json::from_slice(&fs::read(&args.tx_file)?)?;
+    ensure_sign_cmd(&tx_info, "ed25519")?;
+    let preimage = tx_info.preimage();
+    let algorithm = tx_info.algorithm();
+    let tx = Transaction::from(tx_info.transaction.clone()).into_view();
+    let id = signer_id(&tx_info.omnilock_config, preimage.as_ref())?;
+    let keys = SignerKeys::ed25519(vec![args.ed25519_key.clone()]);
+    let signer = build_omnilock_signer(keys.clone(), &tx_info.omnilock_config, algorithm);
+    if !signer.match_id(id.as_bytes()) {
+        bail!(
+            "the ed25519 key does not match hash {:#x} in omnilock config",
+            id
+        );
+    }
+    let (tx, still_locked_groups) = sign_tx_(
+        tx,
+        &tx_info.omnilock_config,
+        preimage.clone(),
+        algorithm,
+        keys,
+        env,
+    )?;
+    let zero_lock = zero_lock(&tx_info.omnilock_config, preimage.as_ref(), algorithm)?;
+    finish_signing(tx, tx_info, zero_lock, &still_locked_groups, &args.tx_file)
 }
 
 fn sign_multisig_tx(args: &SignTxMultisigArgs, env: &ConfigContext) -> Result<()> {
     let tx_info: TxInfo = serde_json::from_slice(&fs::read(&args.tx_file)?)?;
-    let tx = Transaction::from(tx_info.transaction).into_view();
+    ensure_sign_cmd(&tx_info, "multisig")?;
+    let tx = Transaction::from(tx_info.transaction.clone()).into_view();
 
     let previous_lock_field = {
         let witness_args =
             WitnessArgs::from_slice(tx.witnesses().get(0).unwrap().raw_data().as_ref())?;
         witness_args.lock().to_opt().unwrap().raw_data()
     };
-    let (tx, still_locked_groups) =
-        sign_tx_(tx, &tx_info.omnilock_config, args.sender_key.clone(), env)?;
+    let (tx, still_locked_groups) = sign_tx_(
+        tx,
+        &tx_info.omnilock_config,
+        None,
+        DelegateAlgorithm::Secp256k1,
+        SignerKeys::secp256k1(args.sender_key.clone()),
+        env,
+    )?;
     let witness_args = WitnessArgs::from_slice(tx.witnesses().get(0).unwrap().raw_data().as_ref())?;
     let lock_field = witness_args.lock().to_opt().unwrap().raw_data();
     let zero_lock = tx_info.omnilock_config.zero_lock(OmniUnlockMode::Normal)?;
@@ -204,30 +558,180 @@ fn sign_multisig_tx(args: &SignTxMultisigArgs, env: &ConfigContext) -> Result<()
     } else {
         bail!("You may tried signed the second time with the same private key!");
     }
+    save_signed_tx(tx, tx_info, &args.tx_file)
+}
+
+fn sign_ownerlock_tx(args: &SignTxOwnerLockArgs, env: &ConfigContext) -> Result<()> {
+    let tx_info: TxInfo = serde_json::from_slice(&fs::read(&args.tx_file)?)?;
+    ensure_sign_cmd(&tx_info, "owner-lock")?;
+    let tx = Transaction::from(tx_info.transaction.clone()).into_view();
+    let tx_dep_provider = DefaultTransactionDependencyProvider::new(env.ckb_rpc.as_str(), 10);
+    let cell = env.omnilock_info()?;
+
+    // The omnilock cells are unlocked by the owner's input, only the owner's lock needs signatures.
+    let mut unlockers = build_omnilock_unlockers(
+        SignerKeys::default(),
+        tx_info.omnilock_config.clone(),
+        None,
+        DelegateAlgorithm::Secp256k1,
+        cell.script_id,
+    );
+    let keys: Vec<secp256k1::SecretKey> = args.sender_key.iter().map(|k| k.0).collect();
+    let owner_signer: Box<dyn Signer> = Box::new(SecpCkbRawKeySigner::new_with_secret_keys(keys));
+    if let Some(multisig_config) = tx_info.owner_multisig_config.clone() {
+        let unlocker = SecpMultisigUnlocker::from((owner_signer, multisig_config));
+        unlockers.insert(ScriptId::new_type(MULTISIG_TYPE_HASH), Box::new(unlocker));
+    } else {
+        let unlocker = SecpSighashUnlocker::from(owner_signer);
+        unlockers.insert(ScriptId::new_type(SIGHASH_TYPE_HASH), Box::new(unlocker));
+    }
+    let (new_tx, still_locked_groups) = unlock_tx(tx.clone(), &tx_dep_provider, &unlockers)?;
+    if new_tx.witnesses().as_slice() == tx.witnesses().as_slice() {
+        bail!("Failed to sign the transaction, the key(s) may not match the owner lock or have signed!");
+    }
+    if !still_locked_groups.is_empty() {
+        println!("> {} groups left to sign!", still_locked_groups.len());
+    } else if let Some(multisig_config) = tx_info.owner_multisig_config.as_ref() {
+        let config_data = multisig_config.to_witness_data();
+        let sig_len = config_data.len() + multisig_config.threshold() as usize * 65;
+        let empty_n = new_tx
+            .witnesses()
+            .into_iter()
+            .filter_map(|witness| WitnessArgs::from_slice(witness.raw_data().as_ref()).ok())
+            .filter_map(|witness_args| witness_args.lock().to_opt())
+            .map(|lock| lock.raw_data())
+            .find(|lock| lock.len() == sig_len && lock.starts_with(&config_data))
+            .map(|lock| {
+                lock[config_data.len()..]
+                    .chunks(65)
+                    .filter(|sig| sig == &[0u8; 65])
+                    .count()
+            })
+            .unwrap_or_default();
+        if empty_n == 0 {
+            println!("> transaction ready to send!");
+        } else {
+            println!("> {} more signature(s) need !", empty_n);
+        }
+    } else {
+        println!("> transaction ready to send!");
+    }
+    save_signed_tx(new_tx, tx_info, &args.tx_file)
+}
+
+fn sign_acp_deposit_tx(args: &SignTxAcpDepositArgs, env: &ConfigContext) -> Result<()> {
+    let tx_info: TxInfo = serde_json::from_slice(&fs::read(&args.tx_file)?)?;
+    let tx = Transaction::from(tx_info.transaction.clone()).into_view();
+    let tx_dep_provider = DefaultTransactionDependencyProvider::new(env.ckb_rpc.as_str(), 10);
+    let cell = env.omnilock_info()?;
+
+    let signer: Box<dyn Signer> = Box::new(SecpCkbRawKeySigner::new_with_secret_keys(vec![
+        args.sender_key.0,
+    ]));
+    let mut unlockers: HashMap<ScriptId, Box<dyn ScriptUnlocker>> = HashMap::new();
+    unlockers.insert(
+        ScriptId::new_type(SIGHASH_TYPE_HASH),
+        Box::new(SecpSighashUnlocker::from(signer)),
+    );
+    let (new_tx, still_locked_groups) = unlock_tx(tx, &tx_dep_provider, &unlockers)?;
+    // The anyone-can-pay cell is unlocked by the deposit itself, with an empty witness.
+    let not_signed = still_locked_groups
+        .iter()
+        .filter(|group| ScriptId::from(&group.script) != cell.script_id)
+        .count();
+    if not_signed > 0 {
+        bail!(
+            "Failed to sign the transaction, {} groups can not be unlocked with the sender key!",
+            not_signed
+        );
+    }
+    println!("> transaction ready to send!");
+    save_signed_tx(new_tx, tx_info, &args.tx_file)
+}
+
+/// Check the transaction is signed and no script group is left locked, then save it.
+fn finish_signing(
+    tx: TransactionView,
+    tx_info: TxInfo,
+    zero_lock: Bytes,
+    still_locked_groups: &[ScriptGroup],
+    tx_file: &Path,
+) -> Result<()> {
+    let witness_args = WitnessArgs::from_slice(tx.witnesses().get(0).unwrap().raw_data().as_ref())?;
+    let lock_field = witness_args.lock().to_opt().unwrap().raw_data();
+    if lock_field != zero_lock && still_locked_groups.is_empty() {
+        println!("> transaction ready to send!");
+    } else {
+        bail!("Failed to sign the transaction!");
+    }
+    save_signed_tx(tx, tx_info, tx_file)
+}
+
+/// Write the signed transaction back into the transaction info file.
+fn save_signed_tx(tx: TransactionView, tx_info: TxInfo, tx_file: &Path) -> Result<()> {
     let tx_info = TxInfo {
         transaction: json_types::Transaction::from(tx.data()),
-        omnilock_config: tx_info.omnilock_config,
+        ..tx_info
     };
-    fs::write(&args.tx_file, serde_json::to_string_pretty(&tx_info)?)?;
+    fs::write(tx_file, serde_json::to_string_pretty(&tx_info)?)?;
     Ok(())
 }
 
 fn sign_tx_(
     tx: TransactionView,
     omnilock_config: &OmniLockConfig,
-    keys: Vec<PrivkeyWrapper>,
+    preimage: Option<Bytes>,
+    algorithm: DelegateAlgorithm,
+    keys: SignerKeys,
     env: &ConfigContext,
 ) -> Result<(TransactionView, Vec<ScriptGroup>)> {
     // Unlock transaction
     let tx_dep_provider = DefaultTransactionDependencyProvider::new(env.ckb_rpc.as_str(), 10);
 
-    let cell = build_omnilock_cell_dep(
-        env.ckb_rpc.as_str(),
-        &env.omnilock_tx_hash,
-        env.omnilock_index,
-    )?;
+    let cell = env.omnilock_info()?;
 
-    let unlockers = build_omnilock_unlockers(keys, omnilock_config.clone(), cell.type_hash);
+    let unlockers = build_omnilock_unlockers(
+        keys,
+        omnilock_config.clone(),
+        preimage,
+        algorithm,
+        cell.script_id,
+    );
     let (new_tx, new_still_locked_groups) = unlock_tx(tx, &tx_dep_provider, &unlockers)?;
     Ok((new_tx, new_still_locked_groups))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdk_compat::new_omnilock_config;
+    use ckb_types::core::TransactionBuilder;
+
+    fn tx_info(flag: IdentityFlag, algorithm: DelegateAlgorithm) -> TxInfo {
+        let config = new_omnilock_config(flag, H160([0x11; 20])).unwrap();
+        let mut tx_info = TxInfo::new(TransactionBuilder::default().build(), config);
+        tx_info.algorithm = Some(algorithm);
+        tx_info
+    }
+
+    #[test]
+    fn test_ensure_sign_cmd() {
+        for (flag, algorithm, cmd) in [
+            (IdentityFlag::Eos, DelegateAlgorithm::Secp256k1, "eos"),
+            (IdentityFlag::Exec, DelegateAlgorithm::Schnorr, "exec"),
+            (IdentityFlag::Dl, DelegateAlgorithm::Webauthn, "passkey"),
+            (IdentityFlag::Exec, DelegateAlgorithm::Secp256r1, "passkey"),
+            (IdentityFlag::Dl, DelegateAlgorithm::Ed25519, "ed25519"),
+        ] {
+            let tx_info = tx_info(flag, algorithm);
+            ensure_sign_cmd(&tx_info, cmd).unwrap();
+            let err = ensure_sign_cmd(&tx_info, "multisig").unwrap_err();
+            assert!(
+                err.to_string()
+                    .ends_with(&format!("sign it with `sign {}`", cmd)),
+                "{}",
+                err
+            );
+        }
+    }
+}
@@ -1,6 +1,15 @@
-use ckb_sdk::traits::{Signer, SignerError};
+use bitcoin::hashes::{sha256d, Hash};
+use ckb_sdk::{
+    traits::{Signer, SignerError},
+    util::{blake160, keccak160, serialize_signature, zeroize_privkey},
+    SECP256K1,
+};
 
-use ckb_types::{bytes::Bytes, core::TransactionView};
+use ckb_crypto::secp::Pubkey;
+use ckb_types::{bytes::Bytes, core::TransactionView, molecule::hex_string};
+use ed25519_dalek::Signer as _;
+
+use crate::util::{hash160, keccak256};
 pub struct CommonSigner {
     signers: Vec<Box<dyn Signer>>,
 }
@@ -36,3 +45,515 @@ impl Signer for CommonSigner {
         signer.sign(id, message, recoverable, tx)
     }
 }
+
+const BITCOIN_MESSAGE_MAGIC: &str = "Bitcoin Signed Message:\n";
+const DOGECOIN_MESSAGE_MAGIC: &str = "Dogecoin Signed Message:\n";
+
+/// Hash the message the way omnilock verifies a bitcoin like signed message:
+/// the message is hex encoded, prefixed with the magic, then double sha256 hashed.
+pub fn btc_message_hash(magic: &str, message: &[u8]) -> [u8; 32] {
+    let message = hex_string(message);
+    let mut data = Vec::with_capacity(magic.len() + message.len() + 2);
+    data.push(magic.len() as u8);
+    data.extend_from_slice(magic.as_bytes());
+    data.push(message.len() as u8);
+    data.extend_from_slice(message.as_bytes());
+    sha256d::Hash::hash(&data).into_inner()
+}
+
+/// Signer of the bitcoin like auth, the signature is 65 bytes, which starts with a
+/// BIP137 header byte telling omnilock how to hash the recovered public key.
+pub struct BtcMessageSigner {
+    keys: Vec<secp256k1::SecretKey>,
+    magic: &'static str,
+}
+
+impl BtcMessageSigner {
+    pub fn new_bitcoin(keys: Vec<secp256k1::SecretKey>) -> BtcMessageSigner {
+        BtcMessageSigner {
+            keys,
+            magic: BITCOIN_MESSAGE_MAGIC,
+        }
+    }
+
+    pub fn new_dogecoin(keys: Vec<secp256k1::SecretKey>) -> BtcMessageSigner {
+        BtcMessageSigner {
+            keys,
+            magic: DOGECOIN_MESSAGE_MAGIC,
+        }
+    }
+
+    /// Find the key whose address hash is `id`, return it with the base of the header byte.
+    fn find_key(&self, id: &[u8]) -> Option<(&secp256k1::SecretKey, u8)> {
+        self.keys.iter().find_map(|key| {
+            let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, key);
+            let compressed = hash160(&pubkey.serialize());
+            if compressed.as_bytes() == id {
+                // P2PKH with compressed public key, the same hash as P2WPKH
+                return Some((key, 31));
+            }
+            if hash160(&pubkey.serialize_uncompressed()).as_bytes() == id {
+                return Some((key, 27));
+            }
+            let mut redeem_script = vec![0x00, 0x14];
+            redeem_script.extend_from_slice(compressed.as_bytes());
+            if hash160(&redeem_script).as_bytes() == id {
+                // P2SH-P2WPKH
+                return Some((key, 35));
+            }
+            None
+        })
+    }
+}
+
+impl Signer for BtcMessageSigner {
+    fn match_id(&self, id: &[u8]) -> bool {
+        id.len() == 20 && self.find_key(id).is_some()
+    }
+
+    fn sign(
+        &self,
+        id: &[u8],
+        message: &[u8],
+        _recoverable: bool,
+        _tx: &TransactionView,
+    ) -> Result<Bytes, SignerError> {
+        let (key, header) = self.find_key(id).ok_or(SignerError::IdNotFound)?;
+        if message.len() != 32 {
+            return Err(SignerError::InvalidMessage(format!(
+                "expected length: 32, got: {}",
+                message.len()
+            )));
+        }
+        let hash = btc_message_hash(self.magic, message);
+        let msg = secp256k1::Message::from_slice(&hash).expect("Convert to message failed");
+        let (recid, data) = SECP256K1
+            .sign_ecdsa_recoverable(&msg, key)
+            .serialize_compact();
+        let mut signature = vec![header + recid.to_i32() as u8];
+        signature.extend_from_slice(&data);
+        Ok(Bytes::from(signature))
+    }
+}
+
+impl Drop for BtcMessageSigner {
+    fn drop(&mut self) {
+        for key in self.keys.iter_mut() {
+            zeroize_privkey(key);
+        }
+    }
+}
+
+/// Signer of the EOS auth, the auth content is the blake160 hash of the compressed public key,
+/// the signature is in EOS compact format: a header byte followed by r and s.
+pub struct EosSigner {
+    keys: Vec<secp256k1::SecretKey>,
+}
+
+impl EosSigner {
+    pub fn new(keys: Vec<secp256k1::SecretKey>) -> EosSigner {
+        EosSigner { keys }
+    }
+
+    fn find_key(&self, id: &[u8]) -> Option<&secp256k1::SecretKey> {
+        self.keys.iter().find(|key| {
+            let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, key);
+            blake160(&pubkey.serialize()).as_bytes() == id
+        })
+    }
+}
+
+impl Signer for EosSigner {
+    fn match_id(&self, id: &[u8]) -> bool {
+        id.len() == 20 && self.find_key(id).is_some()
+    }
+
+    fn sign(
+        &self,
+        id: &[u8],
+        message: &[u8],
+        _recoverable: bool,
+        _tx: &TransactionView,
+    ) -> Result<Bytes, SignerError> {
+        let key = self.find_key(id).ok_or(SignerError::IdNotFound)?;
+        let msg = secp256k1::Message::from_slice(message)
+            .map_err(|e| SignerError::InvalidMessage(e.to_string()))?;
+        let (recid, data) = SECP256K1
+            .sign_ecdsa_recoverable(&msg, key)
+            .serialize_compact();
+        // 27 + 4 means the public key is compressed
+        let mut signature = vec![31 + recid.to_i32() as u8];
+        signature.extend_from_slice(&data);
+        Ok(Bytes::from(signature))
+    }
+}
+
+impl Drop for EosSigner {
+    fn drop(&mut self) {
+        for key in self.keys.iter_mut() {
+            zeroize_privkey(key);
+        }
+    }
+}
+
+const TRON_MESSAGE_PREFIX: &[u8] = b"\x19TRON Signed Message:\n32";
+
+/// Signer of the tron auth, the auth content is the keccak160 hash of the public key as ethereum,
+/// but the message is prefixed by the tron signed message prefix.
+pub struct TronSigner {
+    keys: Vec<secp256k1::SecretKey>,
+}
+
+impl TronSigner {
+    pub fn new(keys: Vec<secp256k1::SecretKey>) -> TronSigner {
+        TronSigner { keys }
+    }
+
+    fn find_key(&self, id: &[u8]) -> Option<&secp256k1::SecretKey> {
+        self.keys.iter().find(|key| {
+            let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, key);
+            keccak160(Pubkey::from(pubkey).as_ref()).as_bytes() == id
+        })
+    }
+}
+
+impl Signer for TronSigner {
+    fn match_id(&self, id: &[u8]) -> bool {
+        id.len() == 20 && self.find_key(id).is_some()
+    }
+
+    fn sign(
+        &self,
+        id: &[u8],
+        message: &[u8],
+        _recoverable: bool,
+        _tx: &TransactionView,
+    ) -> Result<Bytes, SignerError> {
+        let key = self.find_key(id).ok_or(SignerError::IdNotFound)?;
+        if message.len() != 32 {
+            return Err(SignerError::InvalidMessage(format!(
+                "expected length: 32, got: {}",
+                message.len()
+            )));
+        }
+        let mut data = TRON_MESSAGE_PREFIX.to_vec();
+        data.extend_from_slice(message);
+        let msg =
+            secp256k1::Message::from_slice(&keccak256(&data)).expect("Convert to message failed");
+        let sig = SECP256K1.sign_ecdsa_recoverable(&msg, key);
+        Ok(Bytes::from(serialize_signature(&sig).to_vec()))
+    }
+}
+
+impl Drop for TronSigner {
+    fn drop(&mut self) {
+        for key in self.keys.iter_mut() {
+            zeroize_privkey(key);
+        }
+    }
+}
+
+/// Signer of the BIP340 Schnorr verifier, the id is the blake160 hash of the x-only public key,
+/// the signature is the x-only public key followed by the Schnorr signature of the message.
+pub struct SchnorrSigner {
+    keys: Vec<secp256k1::SecretKey>,
+}
+
+impl SchnorrSigner {
+    pub fn new(keys: Vec<secp256k1::SecretKey>) -> SchnorrSigner {
+        SchnorrSigner { keys }
+    }
+
+    fn find_key(&self, id: &[u8]) -> Option<secp256k1::KeyPair> {
+        self.keys.iter().find_map(|key| {
+            let keypair = secp256k1::KeyPair::from_secret_key(&SECP256K1, key);
+            let (xonly_pubkey, _) = keypair.x_only_public_key();
+            (blake160(&xonly_pubkey.serialize()).as_bytes() == id).then_some(keypair)
+        })
+    }
+}
+
+impl Signer for SchnorrSigner {
+    fn match_id(&self, id: &[u8]) -> bool {
+        id.len() == 20 && self.find_key(id).is_some()
+    }
+
+    fn sign(
+        &self,
+        id: &[u8],
+        message: &[u8],
+        _recoverable: bool,
+        _tx: &TransactionView,
+    ) -> Result<Bytes, SignerError> {
+        let keypair = self.find_key(id).ok_or(SignerError::IdNotFound)?;
+        let msg = secp256k1::Message::from_slice(message)
+            .map_err(|e| SignerError::InvalidMessage(e.to_string()))?;
+        let sig = SECP256K1.sign_schnorr_no_aux_rand(&msg, &keypair);
+        let mut signature = keypair.x_only_public_key().0.serialize().to_vec();
+        signature.extend_from_slice(sig.as_ref());
+        Ok(Bytes::from(signature))
+    }
+}
+
+impl Drop for SchnorrSigner {
+    fn drop(&mut self) {
+        for key in self.keys.iter_mut() {
+            zeroize_privkey(key);
+        }
+    }
+}
+
+/// Signer of the ed25519 verifier, the id is the blake160 hash of the public key, the signature
+/// is the public key followed by the ed25519 signature of the message. The keys are zeroized
+/// on drop by ed25519-dalek.
+pub struct Ed25519Signer {
+    keys: Vec<ed25519_dalek::SigningKey>,
+}
+
+impl Ed25519Signer {
+    pub fn new(keys: Vec<ed25519_dalek::SigningKey>) -> Ed25519Signer {
+        Ed25519Signer { keys }
+    }
+
+    fn find_key(&self, id: &[u8]) -> Option<&ed25519_dalek::SigningKey> {
+        self.keys
+            .iter()
+            .find(|key| blake160(key.verifying_key().as_bytes()).as_bytes() == id)
+    }
+}
+
+impl Signer for Ed25519Signer {
+    fn match_id(&self, id: &[u8]) -> bool {
+        id.len() == 20 && self.find_key(id).is_some()
+    }
+
+    fn sign(
+        &self,
+        id: &[u8],
+        message: &[u8],
+        _recoverable: bool,
+        _tx: &TransactionView,
+    ) -> Result<Bytes, SignerError> {
+        let key = self.find_key(id).ok_or(SignerError::IdNotFound)?;
+        let mut signature = key.verifying_key().as_bytes().to_vec();
+        signature.extend_from_slice(&key.sign(message).to_bytes());
+        Ok(Bytes::from(signature))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        arg_parser::{ArgParser, BitcoinAddressParser},
+        hd_wallet::derive_privkey,
+    };
+    use bitcoin::util::bip32::DerivationPath;
+    use ckb_types::core::TransactionBuilder;
+    use std::str::FromStr;
+
+    // The BIP49 test vector, the first receiving address of account 0 on the testnet
+    const BIP49_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const BIP49_PATH: &str = "m/49'/1'/0'/0/0";
+    const BIP49_ADDRESS: &str = "2Mww8dCYPUpKHofjgcXcBCEGmniw9CoaiD2";
+
+    // The private key 1, whose public key is the generator point
+    const ONE_PRIVKEY: [u8; 32] = {
+        let mut key = [0u8; 32];
+        key[31] = 1;
+        key
+    };
+    // The hash160 of the compressed and the uncompressed public key, as P2PKH addresses
+    // 1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH and 1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm
+    const ONE_HASH160_COMPRESSED: &str = "751e76e8199196d454941c45d1b3a323f1433bd6";
+    const ONE_HASH160_UNCOMPRESSED: &str = "91b24bf9f5288532960ac687abb035127b1d28a5";
+    // The ethereum address 0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf
+    const ONE_KECCAK160: &str = "7e5f4552091a69125d5dfcb7b8c2659029395bdf";
+    const ONE_BLAKE160: &str = "75178f34549c5fe9cd1a0c57aebd01e7ddf9249e";
+
+    // The digests of the message [0x11; 32] with the magics and the prefix
+    const BITCOIN_MESSAGE_HASH: &str =
+        "c93d43c303feba518670e28e928876f7e880e1541f5c6bc03036d7b7d78e0b91";
+    const DOGECOIN_MESSAGE_HASH: &str =
+        "03e3f174ae872de676e8b370842eb4b44b56d7ceb45c90b3adb04b20d4a014fa";
+    const TRON_MESSAGE_HASH: &str =
+        "95f3e463685c7acbd2ac4d6bab2fb71a67c340e558aeb7dc009a1359d33851f1";
+
+    fn hex_bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// Recover the public key from the compact signature and the recovery id over the digest.
+    fn recover(compact: &[u8], recid: u8, digest: &[u8]) -> secp256k1::PublicKey {
+        let recid = secp256k1::ecdsa::RecoveryId::from_i32(recid as i32).unwrap();
+        let sig = secp256k1::ecdsa::RecoverableSignature::from_compact(compact, recid).unwrap();
+        let msg = secp256k1::Message::from_slice(digest).unwrap();
+        SECP256K1.recover_ecdsa(&msg, &sig).unwrap()
+    }
+
+    #[test]
+    fn test_btc_message_hash() {
+        let message = [0x11u8; 32];
+        assert_eq!(
+            btc_message_hash(BITCOIN_MESSAGE_MAGIC, &message).to_vec(),
+            hex_bytes(BITCOIN_MESSAGE_HASH)
+        );
+        assert_eq!(
+            btc_message_hash(DOGECOIN_MESSAGE_MAGIC, &message).to_vec(),
+            hex_bytes(DOGECOIN_MESSAGE_HASH)
+        );
+    }
+
+    #[test]
+    fn test_btc_message_signer() {
+        let key = secp256k1::SecretKey::from_slice(&ONE_PRIVKEY).unwrap();
+        let pubkey = secp256k1::PublicKey::from_secret_key(&SECP256K1, &key);
+        let message = [0x11u8; 32];
+        let tx = TransactionBuilder::default().build();
+        let cases = [
+            (
+                BtcMessageSigner::new_bitcoin(vec![key]),
+                ONE_HASH160_COMPRESSED,
+                31,
+                BITCOIN_MESSAGE_HASH,
+            ),
+            (
+                BtcMessageSigner::new_bitcoin(vec![key]),
+                ONE_HASH160_UNCOMPRESSED,
+                27,
+                BITCOIN_MESSAGE_HASH,
+            ),
+            (
+                BtcMessageSigner::new_dogecoin(vec![key]),
+                ONE_HASH160_COMPRESSED,
+                31,
+                DOGECOIN_MESSAGE_HASH,
+            ),
+        ];
+        for (signer, id, header, digest) in cases {
+            let id = hex_bytes(id);
+            assert!(signer.match_id(&id));
+            let signature = signer.sign(&id, &message, true, &tx).unwrap();
+            assert_eq!(signature.len(), 65);
+            assert!((header..header + 4).contains(&signature[0]));
+            let recovered = recover(&signature[1..], signature[0] - header, &hex_bytes(digest));
+            assert_eq!(recovered, pubkey);
+        }
+    }
+
+    #[test]
+    fn test_eos_signer() {
+        let key = secp256k1::SecretKey::from_slice(&ONE_PRIVKEY).unwrap();
+        let id = hex_bytes(ONE_BLAKE160);
+        let signer = EosSigner::new(vec![key]);
+        assert!(signer.match_id(&id));
+        assert!(!signer.match_id(&hex_bytes(ONE_HASH160_COMPRESSED)));
+
+        let message = [0x11u8; 32];
+        let tx = TransactionBuilder::default().build();
+        let signature = signer.sign(&id, &message, true, &tx).unwrap();
+        assert_eq!(signature.len(), 65);
+        // the header is 27 + 4 (compressed) + recid
+        assert!((31..=34).contains(&signature[0]));
+        let recovered = recover(&signature[1..], signature[0] - 31, &message);
+        assert_eq!(
+            recovered,
+            secp256k1::PublicKey::from_secret_key(&SECP256K1, &key)
+        );
+    }
+
+    #[test]
+    fn test_tron_signer() {
+        let key = secp256k1::SecretKey::from_slice(&ONE_PRIVKEY).unwrap();
+        let id = hex_bytes(ONE_KECCAK160);
+        let signer = TronSigner::new(vec![key]);
+        assert!(signer.match_id(&id));
+
+        let message = [0x11u8; 32];
+        let tx = TransactionBuilder::default().build();
+        let signature = signer.sign(&id, &message, true, &tx).unwrap();
+        assert_eq!(signature.len(), 65);
+        // r, s and the recovery id as the last byte
+        assert!(signature[64] <= 3);
+        let recovered = recover(
+            &signature[..64],
+            signature[64],
+            &hex_bytes(TRON_MESSAGE_HASH),
+        );
+        assert_eq!(
+            recovered,
+            secp256k1::PublicKey::from_secret_key(&SECP256K1, &key)
+        );
+    }
+
+    #[test]
+    fn test_p2sh_p2wpkh_signature_header() {
+        let seed = bip39::Mnemonic::parse(BIP49_MNEMONIC).unwrap().to_seed("");
+        let path = DerivationPath::from_str(BIP49_PATH).unwrap();
+        let key = *derive_privkey(&seed, &path).unwrap();
+        let id = BitcoinAddressParser::parse(BIP49_ADDRESS).unwrap();
+        let signer = BtcMessageSigner::new_bitcoin(vec![key]);
+        assert!(signer.match_id(id.as_bytes()));
+
+        let message = [0x11u8; 32];
+        let tx = TransactionBuilder::default().build();
+        let signature = signer.sign(id.as_bytes(), &message, true, &tx).unwrap();
+        assert_eq!(signature.len(), 65);
+        assert!((35..=38).contains(&signature[0]));
+
+        let recid = secp256k1::ecdsa::RecoveryId::from_i32((signature[0] - 35) as i32).unwrap();
+        let sig =
+            secp256k1::ecdsa::RecoverableSignature::from_compact(&signature[1..], recid).unwrap();
+        let hash = btc_message_hash(BITCOIN_MESSAGE_MAGIC, &message);
+        let msg = secp256k1::Message::from_slice(&hash).unwrap();
+        let pubkey = SECP256K1.recover_ecdsa(&msg, &sig).unwrap();
+        assert_eq!(
+            pubkey,
+            secp256k1::PublicKey::from_secret_key(&SECP256K1, &key)
+        );
+        let mut redeem_script = vec![0x00, 0x14];
+        redeem_script.extend_from_slice(hash160(&pubkey.serialize()).as_bytes());
+        assert_eq!(hash160(&redeem_script), id);
+    }
+
+    #[test]
+    fn test_schnorr_signer() {
+        let key = secp256k1::SecretKey::from_slice(&[0x11; 32]).unwrap();
+        let (xonly_pubkey, _) =
+            secp256k1::KeyPair::from_secret_key(&SECP256K1, &key).x_only_public_key();
+        let id = blake160(&xonly_pubkey.serialize());
+        let signer = SchnorrSigner::new(vec![key]);
+        assert!(signer.match_id(id.as_bytes()));
+        assert!(!signer.match_id(&[0x22; 20]));
+
+        let message = [0x33u8; 32];
+        let tx = TransactionBuilder::default().build();
+        let signature = signer.sign(id.as_bytes(), &message, true, &tx).unwrap();
+        assert_eq!(signature.len(), 96);
+        assert_eq!(&signature[..32], &xonly_pubkey.serialize()[..]);
+        let sig = secp256k1::schnorr::Signature::from_slice(&signature[32..]).unwrap();
+        let msg = secp256k1::Message::from_slice(&message).unwrap();
+        SECP256K1.verify_schnorr(&sig, &msg, &xonly_pubkey).unwrap();
+    }
+
+    #[test]
+    fn test_ed25519_signer() {
+        let key = ed25519_dalek::SigningKey::from_bytes(&[0x11; 32]);
+        let pubkey = key.verifying_key();
+        let id = blake160(pubkey.as_bytes());
+        let signer = Ed25519Signer::new(vec![key]);
+        assert!(signer.match_id(id.as_bytes()));
+        assert!(!signer.match_id(&[0x22; 20]));
+
+        let message = [0x33u8; 32];
+        let tx = TransactionBuilder::default().build();
+        let signature = signer.sign(id.as_bytes(), &message, true, &tx).unwrap();
+        assert_eq!(signature.len(), 96);
+        assert_eq!(&signature[..32], pubkey.as_bytes());
+        let sig = ed25519_dalek::Signature::from_slice(&signature[32..]).unwrap();
+        ed25519_dalek::Verifier::verify(&pubkey, &message, &sig).unwrap();
+    }
+}
@@ -0,0 +1,109 @@
+use anyhow::{anyhow, ensure, Result};
+use ckb_sdk::{CkbRpcClient, Since, SinceType};
+use ckb_types::{core::EpochNumberWithFraction, packed::OutPoint, prelude::*, H256};
+
+/// Format a since value in the format `SinceArgParser` accepts.
+pub fn since_string(since: u64) -> String {
+    let since = Since::from_raw_value(since);
+    let prefix = if since.is_relative() { "relative:" } else { "" };
+    match since.extract_metric() {
+        Some((SinceType::BlockNumber, value)) => format!("{}block:{}", prefix, value),
+        Some((SinceType::EpochNumberWithFraction, value)) => {
+            let epoch = EpochNumberWithFraction::from_full_value(value);
+            format!(
+                "{}epoch:{}.{}/{}",
+                prefix,
+                epoch.number(),
+                epoch.index(),
+                epoch.length()
+            )
+        }
+        Some((SinceType::Timestamp, value)) => format!("{}timestamp:{}", prefix, value),
+        None => format!("{:#x}", since.value()),
+    }
+}
+
+/// The position on the chain a since value is compared with.
+struct ChainPoint {
+    number: u64,
+    epoch: EpochNumberWithFraction,
+    /// median time of the past blocks, in seconds
+    median_time: u64,
+}
+
+impl ChainPoint {
+    fn of_block(ckb_client: &mut CkbRpcClient, block_hash: H256) -> Result<ChainPoint> {
+        let header = ckb_client
+            .get_header(block_hash.clone())?
+            .ok_or_else(|| anyhow!("can not find block {:#x}", block_hash))?;
+        let median_time = ckb_client
+            .get_block_median_time(block_hash.clone())?
+            .ok_or_else(|| anyhow!("can not get the median time of block {:#x}", block_hash))?;
+        Ok(ChainPoint {
+            number: header.inner.number.value(),
+            epoch: EpochNumberWithFraction::from_full_value(header.inner.epoch.value()),
+            median_time: median_time.value() / 1000,
+        })
+    }
+}
+
+/// `a + b >= c` of epochs with fraction.
+fn epoch_reached(
+    a: EpochNumberWithFraction,
+    b: EpochNumberWithFraction,
+    c: EpochNumberWithFraction,
+) -> bool {
+    let to_fraction = |e: EpochNumberWithFraction| {
+        let length = e.length().max(1) as u128;
+        (e.number() as u128 * length + e.index() as u128, length)
+    };
+    let ((a, la), (b, lb), (c, lc)) = (to_fraction(a), to_fraction(b), to_fraction(c));
+    a * lb * lc + b * la * lc >= c * la * lb
+}
+
+/// Check the time lock of the input cell is satisfied at the tip of the chain, so the
+/// transaction can be committed in the next block.
+pub fn check_since_satisfied(
+    ckb_client: &mut CkbRpcClient,
+    out_point: &OutPoint,
+    since: u64,
+) -> Result<()> {
+    let since = Since::from_raw_value(since);
+    let (metric, value) = since
+        .extract_metric()
+        .ok_or_else(|| anyhow!("invalid since value {:#x}", since.value()))?;
+    let tip = ckb_client.get_tip_header()?;
+    let tip = ChainPoint::of_block(ckb_client, tip.hash)?;
+    let base = if since.is_relative() {
+        let tx_hash: H256 = out_point.tx_hash().unpack();
+        let block_hash = ckb_client
+            .get_transaction(tx_hash.clone())?
+            .and_then(|tx| tx.tx_status.block_hash)
+            .ok_or_else(|| anyhow!("the transaction {:#x} is not committed", tx_hash))?;
+        ChainPoint::of_block(ckb_client, block_hash)?
+    } else {
+        ChainPoint {
+            number: 0,
+            epoch: EpochNumberWithFraction::new(0, 0, 1),
+            median_time: 0,
+        }
+    };
+    let reached = match metric {
+        SinceType::BlockNumber => base.number + value <= tip.number + 1,
+        SinceType::EpochNumberWithFraction => epoch_reached(
+            base.epoch,
+            EpochNumberWithFraction::from_full_value(value),
+            tip.epoch,
+        ),
+        SinceType::Timestamp => base.median_time + value <= tip.median_time,
+    };
+    let index: u32 = out_point.index().unpack();
+    ensure!(
+        reached,
+        "the time lock {} of cell {:#x}:{} is not satisfied yet",
+        since_string(since.value()),
+        out_point.tx_hash(),
+        index
+    );
+    Ok(())
+}
@@ -1,9 +1,43 @@
 use ckb_jsonrpc_types as json_types;
-use ckb_sdk::unlock::OmniLockConfig;
+use ckb_sdk::unlock::{MultisigConfig, OmniLockConfig};
+use ckb_types::{bytes::Bytes, core::TransactionView};
 use serde::{Deserialize, Serialize};
 
+use crate::unlock::DelegateAlgorithm;
+
 #[derive(Serialize, Deserialize)]
 pub struct TxInfo {
     pub transaction: json_types::Transaction,
     pub omnilock_config: OmniLockConfig,
+    /// The multisig config of the owner lock input, if the owner of the omnilock cell is a multisig address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_multisig_config: Option<MultisigConfig>,
+    /// The preimage of the auth content, put into the witness lock when signing exec auth cells
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preimage: Option<json_types::JsonBytes>,
+    /// The signature algorithm the exec script or the dynamic library verifies, secp256k1 if not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<DelegateAlgorithm>,
+}
+
+impl TxInfo {
+    pub fn new(tx: TransactionView, omnilock_config: OmniLockConfig) -> Self {
+        TxInfo {
+            transaction: json_types::TransactionView::from(tx).inner,
+            omnilock_config,
+            owner_multisig_config: None,
+            preimage: None,
+            algorithm: None,
+        }
+    }
+
+    pub fn preimage(&self) -> Option<Bytes> {
+        self.preimage
+            .as_ref()
+            .map(|preimage| preimage.clone().into_bytes())
+    }
+
+    pub fn algorithm(&self) -> DelegateAlgorithm {
+        self.algorithm.unwrap_or_default()
+    }
 }
@@ -0,0 +1,319 @@
+use anyhow::{anyhow, ensure, Result};
+use ckb_sdk::{
+    traits::{Signer, TransactionDependencyProvider},
+    types::omni_lock::OmniLockWitnessLock,
+    unlock::{
+        fill_witness_lock, generate_message, IdentityFlag, OmniLockConfig, OmniLockScriptSigner,
+        OmniUnlockMode, ScriptSignError, ScriptUnlocker, UnlockError,
+    },
+    util::blake160,
+    ScriptGroup,
+};
+use ckb_types::{
+    bytes::{BufMut, Bytes, BytesMut},
+    core::{ScriptHashType, TransactionView},
+    packed::{self, WitnessArgs},
+    prelude::*,
+    H160, H256,
+};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// The length of the secp256r1 signature in the witness lock. Omnilock has no built-in P-256
+/// verifier, the exec script or the dynamic library delegated to reads this layout:
+///
+/// | offset | length | content                                                      |
+/// |--------|--------|--------------------------------------------------------------|
+/// | 0      | 64     | the P-256 public key x \| y, big endian                      |
+/// | 64     | 64     | the ECDSA signature r \| s, big endian, s in the lower half  |
+///
+/// The verifier checks the blake160 hash of x | y is the pubkey hash of the preimage, and the
+/// signature is of the SHA-256 hash of the 32 bytes signature message.
+pub const SECP256R1_SIGNATURE_LEN: usize = 128;
+
+/// The length of the WebAuthn signature in the witness lock, the verifier reads this layout:
+///
+/// | offset      | length      | content                                                   |
+/// |-------------|-------------|-----------------------------------------------------------|
+/// | 0           | 64          | the P-256 public key x \| y, big endian                   |
+/// | 64          | 64          | the ECDSA signature r \| s, big endian, s in the lower half |
+/// | 128         | 2           | the authenticator data length n, little endian            |
+/// | 130         | 2           | the client data JSON length m, little endian              |
+/// | 132         | n           | the authenticator data                                    |
+/// | 132 + n     | m           | the client data JSON                                      |
+/// | 132 + n + m | 512 - n - m | zero padding                                              |
+///
+/// The padding keeps the length fixed, so the signature message can be computed before the
+/// assertion is made. The verifier checks the blake160 hash of x | y is the pubkey hash of the
+/// preimage, the client data JSON has the type `webauthn.get` and the challenge of the signature
+/// message in base64url without padding, the user present flag of the authenticator data is set,
+/// and the signature is of authenticator data | SHA-256(client data JSON).
+pub const WEBAUTHN_SIGNATURE_LEN: usize = 644;
+
+/// The length of the WebAuthn signature before the authenticator data: the public key, the
+/// signature and the two lengths.
+pub const WEBAUTHN_HEADER_LEN: usize = 132;
+
+/// The signature algorithm the exec script or the dynamic library verifies, it decides the
+/// length of the signature in the witness lock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum DelegateAlgorithm {
+    /// The recoverable secp256k1 signature of the message, 65 bytes
+    #[default]
+    Secp256k1,
+    /// The P-256 ECDSA signature of the SHA-256 hash of the message, see `SECP256R1_SIGNATURE_LEN`
+    Secp256r1,
+    /// The P-256 WebAuthn assertion of a passkey whose challenge is the message, see
+    /// `WEBAUTHN_SIGNATURE_LEN`
+    Webauthn,
+    /// The BIP340 Schnorr signature of the message: x-only pubkey(32) | signature(64)
+    Schnorr,
+    /// The ed25519 signature of the message: pubkey(32) | signature(64)
+    Ed25519,
+}
+
+impl DelegateAlgorithm {
+    /// The name of the algorithm, as the value of `--algorithm`.
+    pub fn name(self) -> &'static str {
+        match self {
+            DelegateAlgorithm::Secp256k1 => "secp256k1",
+            DelegateAlgorithm::Secp256r1 => "secp256r1",
+            DelegateAlgorithm::Webauthn => "webauthn",
+            DelegateAlgorithm::Schnorr => "schnorr",
+            DelegateAlgorithm::Ed25519 => "ed25519",
+        }
+    }
+
+    pub fn signature_len(self) -> usize {
+        match self {
+            DelegateAlgorithm::Secp256k1 => 65,
+            DelegateAlgorithm::Secp256r1 => SECP256R1_SIGNATURE_LEN,
+            DelegateAlgorithm::Webauthn => WEBAUTHN_SIGNATURE_LEN,
+            DelegateAlgorithm::Schnorr | DelegateAlgorithm::Ed25519 => 96,
+        }
+    }
+}
+
+/// Build the preimage of the exec auth, the auth content is the blake160 hash of it.
+/// The layout is code hash(32) | hash type(1) | place(1) | bounds(8, little endian) | pubkey hash(20).
+pub fn exec_preimage(
+    code_hash: &H256,
+    hash_type: ScriptHashType,
+    place: u8,
+    bounds: u64,
+    pubkey_hash: &H160,
+) -> Bytes {
+    let mut preimage = BytesMut::with_capacity(62);
+    preimage.put(code_hash.as_bytes());
+    preimage.put_u8(hash_type as u8);
+    preimage.put_u8(place);
+    preimage.put_u64_le(bounds);
+    preimage.put(pubkey_hash.as_bytes());
+    preimage.freeze()
+}
+
+/// Build the preimage of the dynamic linking auth, the auth content is the blake160 hash of it.
+/// The layout is code hash(32) | hash type(1) | pubkey hash(20).
+pub fn dl_preimage(code_hash: &H256, hash_type: ScriptHashType, pubkey_hash: &H160) -> Bytes {
+    let mut preimage = BytesMut::with_capacity(53);
+    preimage.put(code_hash.as_bytes());
+    preimage.put_u8(hash_type as u8);
+    preimage.put(pubkey_hash.as_bytes());
+    preimage.freeze()
+}
+
+/// The identity the signer signs with. It's the auth content itself, except for the exec and
+/// dynamic linking auth, whose preimage carries the pubkey hash as its last 20 bytes.
+pub fn signer_id(config: &OmniLockConfig, preimage: Option<&Bytes>) -> Result<H160> {
+    let auth_content = config.id().auth_content();
+    if !matches!(config.id().flag(), IdentityFlag::Exec | IdentityFlag::Dl) {
+        return Ok(auth_content.clone());
+    }
+    let preimage =
+        preimage.ok_or_else(|| anyhow!("the preimage of the auth content is missing"))?;
+    ensure!(
+        preimage.len() >= 20 && &blake160(preimage) == auth_content,
+        "the preimage does not match the auth content {:#x}",
+        auth_content
+    );
+    Ok(H160::from_slice(&preimage[preimage.len() - 20..])?)
+}
+
+/// Check if the auth flag can be signed by the omnilock unlocker of ckb-sdk.
+pub fn is_sdk_supported(config: &OmniLockConfig) -> bool {
+    matches!(
+        config.id().flag(),
+        IdentityFlag::PubkeyHash
+            | IdentityFlag::Ethereum
+            | IdentityFlag::Multisig
+            | IdentityFlag::OwnerLock
+    )
+}
+
+/// Build the placeholder witness lock, the signature message is computed with a zero lock of the same length.
+pub fn placeholder_witness_lock(
+    config: &OmniLockConfig,
+    preimage: Option<&Bytes>,
+    algorithm: DelegateAlgorithm,
+) -> Result<Bytes> {
+    if is_sdk_supported(config) {
+        return Ok(config.placeholder_witness_lock(OmniUnlockMode::Normal)?);
+    }
+    let signature = vec![0u8; algorithm.signature_len()];
+    let lock = OmniLockWitnessLock::new_builder()
+        .signature(Some(Bytes::from(signature)).pack())
+        .preimage(preimage.cloned().pack())
+        .build();
+    Ok(lock.as_bytes())
+}
+
+/// Build the placeholder witness of an omnilock input.
+pub fn placeholder_witness(
+    config: &OmniLockConfig,
+    preimage: Option<&Bytes>,
+    algorithm: DelegateAlgorithm,
+) -> Result<WitnessArgs> {
+    if is_sdk_supported(config) {
+        return Ok(config.placeholder_witness(OmniUnlockMode::Normal)?);
+    }
+    let lock = placeholder_witness_lock(config, preimage, algorithm)?;
+    Ok(WitnessArgs::new_builder().lock(Some(lock).pack()).build())
+}
+
+/// Build zero lock content for signature
+pub fn zero_lock(
+    config: &OmniLockConfig,
+    preimage: Option<&Bytes>,
+    algorithm: DelegateAlgorithm,
+) -> Result<Bytes> {
+    let len = placeholder_witness_lock(config, preimage, algorithm)?.len();
+    Ok(Bytes::from(vec![0u8; len]))
+}
+
+/// Unlocker of the auth types ckb-sdk can't sign yet, the signer is responsible for
+/// converting the message the way the auth type requires.
+pub struct OmniLockSigUnlocker {
+    signer: Box<dyn Signer>,
+    config: OmniLockConfig,
+    preimage: Option<Bytes>,
+    algorithm: DelegateAlgorithm,
+}
+
+impl OmniLockSigUnlocker {
+    pub fn new(
+        signer: Box<dyn Signer>,
+        config: OmniLockConfig,
+        preimage: Option<Bytes>,
+        algorithm: DelegateAlgorithm,
+    ) -> OmniLockSigUnlocker {
+        OmniLockSigUnlocker {
+            signer,
+            config,
+            preimage,
+            algorithm,
+        }
+    }
+}
+
+impl ScriptUnlocker for OmniLockSigUnlocker {
+    fn match_args(&self, args: &[u8]) -> bool {
+        self.config.build_args().as_ref() == args
+    }
+
+    fn unlock(
+        &self,
+        tx: &TransactionView,
+        script_group: &ScriptGroup,
+        _tx_dep_provider: &dyn TransactionDependencyProvider,
+    ) -> Result<TransactionView, UnlockError> {
+        let witness_idx = script_group.input_indices[0];
+        let mut witnesses: Vec<packed::Bytes> = tx.witnesses().into_iter().collect();
+        while witnesses.len() <= witness_idx {
+            witnesses.push(Default::default());
+        }
+        let tx_new = tx
+            .as_advanced_builder()
+            .set_witnesses(witnesses.clone())
+            .build();
+
+        let preimage = self.preimage.as_ref();
+        let zero_lock = zero_lock(&self.config, preimage, self.algorithm)?;
+        let message = generate_message(&tx_new, script_group, zero_lock)?;
+        let id = signer_id(&self.config, preimage)?;
+        let signature = self
+            .signer
+            .sign(id.as_bytes(), message.as_ref(), true, tx)
+            .map_err(ScriptSignError::from)?;
+
+        // Put signature into witness
+        let witness_data = witnesses[witness_idx].raw_data();
+        let mut current_witness: WitnessArgs = if witness_data.is_empty() {
+            WitnessArgs::default()
+        } else {
+            WitnessArgs::from_slice(witness_data.as_ref())
+                .map_err(|_| UnlockError::InvalidWitnessArgs(witness_idx))?
+        };
+        let lock = OmniLockScriptSigner::build_witness_lock(current_witness.lock(), signature)?;
+        let lock = OmniLockWitnessLock::from_slice(lock.as_ref())
+            .map_err(ScriptSignError::from)?
+            .as_builder()
+            .preimage(self.preimage.clone().pack())
+            .build()
+            .as_bytes();
+        current_witness = current_witness.as_builder().lock(Some(lock).pack()).build();
+        witnesses[witness_idx] = current_witness.as_bytes().pack();
+        Ok(tx.as_advanced_builder().set_witnesses(witnesses).build())
+    }
+
+    fn fill_placeholder_witness(
+        &self,
+        tx: &TransactionView,
+        script_group: &ScriptGroup,
+        _tx_dep_provider: &dyn TransactionDependencyProvider,
+    ) -> Result<TransactionView, UnlockError> {
+        let lock = placeholder_witness_lock(&self.config, self.preimage.as_ref(), self.algorithm)?;
+        fill_witness_lock(tx, script_group, lock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE_HASH: H256 = H256([0x11; 32]);
+    const PUBKEY_HASH: H160 = H160([0x22; 20]);
+
+    #[test]
+    fn test_exec_preimage() {
+        let bounds = 0x0000_0010_0000_0020;
+        let preimage = exec_preimage(&CODE_HASH, ScriptHashType::Data1, 1, bounds, &PUBKEY_HASH);
+        assert_eq!(preimage.len(), 62);
+        assert_eq!(&preimage[..32], CODE_HASH.as_bytes());
+        assert_eq!(preimage[32], ScriptHashType::Data1 as u8);
+        assert_eq!(preimage[33], 1);
+        assert_eq!(&preimage[34..42], &bounds.to_le_bytes());
+        assert_eq!(&preimage[42..], PUBKEY_HASH.as_bytes());
+    }
+
+    #[test]
+    fn test_dl_preimage() {
+        let preimage = dl_preimage(&CODE_HASH, ScriptHashType::Type, &PUBKEY_HASH);
+        assert_eq!(preimage.len(), 53);
+        assert_eq!(&preimage[..32], CODE_HASH.as_bytes());
+        assert_eq!(preimage[32], ScriptHashType::Type as u8);
+        assert_eq!(&preimage[33..], PUBKEY_HASH.as_bytes());
+    }
+
+    #[test]
+    fn test_signer_id_of_preimage() {
+        let preimage = dl_preimage(&CODE_HASH, ScriptHashType::Type, &PUBKEY_HASH);
+        let config =
+            crate::sdk_compat::new_omnilock_config(IdentityFlag::Dl, blake160(&preimage)).unwrap();
+        assert_eq!(signer_id(&config, Some(&preimage)).unwrap(), PUBKEY_HASH);
+        assert!(signer_id(&config, None).is_err());
+
+        let other = dl_preimage(&CODE_HASH, ScriptHashType::Data, &PUBKEY_HASH);
+        assert!(signer_id(&config, Some(&other)).is_err());
+    }
+}
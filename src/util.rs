@@ -1,3 +1,16 @@
+use std::{
+    fs::{File, OpenOptions},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use bitcoin::{
+    hashes::{hash160, ripemd160, Hash},
+    util::base58,
+};
+use ckb_types::H160;
+use sha3::{Digest, Keccak256};
+
 pub fn strip_prefix_0x(s: &str) -> &str {
     if s.starts_with("0x") || s.starts_with("0X") {
         &s[2..]
@@ -5,3 +18,47 @@ pub fn strip_prefix_0x(s: &str) -> &str {
         s
     }
 }
+
+/// ripemd160(sha256(data)), the hash used by bitcoin like addresses.
+pub fn hash160(data: &[u8]) -> H160 {
+    H160::from_slice(&hash160::Hash::hash(data)[..]).unwrap()
+}
+
+/// The 4 bytes checksum of an EOS public key, `suffix` is the key type, it is empty for legacy keys.
+pub fn eos_pubkey_checksum(pubkey: &[u8], suffix: &[u8]) -> [u8; 4] {
+    let mut data = pubkey.to_vec();
+    data.extend_from_slice(suffix);
+    let mut checksum = [0u8; 4];
+    checksum.copy_from_slice(&ripemd160::Hash::hash(&data)[..4]);
+    checksum
+}
+
+/// Format a public key in EOS `PUB_K1_` format.
+pub fn eos_pubkey_string(pubkey: &secp256k1::PublicKey) -> String {
+    let mut data = pubkey.serialize().to_vec();
+    data.extend_from_slice(&eos_pubkey_checksum(&data, b"K1"));
+    format!("PUB_K1_{}", base58::encode_slice(&data))
+}
+
+/// The version byte of tron addresses.
+pub const TRON_ADDRESS_PREFIX: u8 = 0x41;
+
+/// Format the keccak160 hash of a public key as a tron address.
+pub fn tron_address_string(hash: &H160) -> String {
+    let mut data = vec![TRON_ADDRESS_PREFIX];
+    data.extend_from_slice(hash.as_bytes());
+    base58::check_encode_slice(&data)
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Create a file to write, an existing file is never overwritten.
+pub fn create_new_file(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .with_context(|| format!("try to create file {}", path.display()))
+}